@@ -0,0 +1,148 @@
+use crate::graph::*;
+use crate::shortest_path::bellman_ford;
+use std::collections::BTreeMap;
+use std::ops::{Add,Neg,Sub};
+use num::Zero;
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum Either<U> {
+    Feasible(Vec<U>),
+    // indices (in insertion order) of constraints whose sum is contradictory
+    Conflict(Vec<usize>),
+}
+
+// Variables x_0..x_{size-1} and a constant 0, stored internally as vertex size.
+pub struct DifferenceConstraints<U> {
+    size: usize,
+    constraints: Vec<(usize,usize,U)>,
+}
+
+impl<U> DifferenceConstraints<U>
+    where U: Add<Output = U> + Sub<Output = U> + Neg<Output = U> + Zero + PartialOrd + Copy,
+{
+    pub fn new(size: usize) -> Self {
+        DifferenceConstraints {
+            size,
+            constraints: Vec::new(),
+        }
+    }
+
+    // x_v - x_u <= c
+    pub fn add(&mut self, u: usize, v: usize, c: U) -> usize {
+        self.constraints.push((u,v,c));
+        self.constraints.len() - 1
+    }
+
+    // x_v <= c
+    pub fn upper(&mut self, v: usize, c: U) -> usize {
+        self.add(self.size,v,c)
+    }
+
+    // x_v >= c
+    pub fn lower(&mut self, v: usize, c: U) -> usize {
+        self.add(v,self.size,-c)
+    }
+
+    fn mk_graph(&self, reverse: bool) -> ListGraph<U> {
+        let mut graph: ListGraph<U> = Graph::new(self.size + 2);
+        for &(u,v,c) in &self.constraints {
+            if reverse {
+                graph.add_edge(v,u,c);
+            } else {
+                graph.add_edge(u,v,c);
+            }
+        }
+        graph
+    }
+
+    // Some assignment satisfying every constraint, or a contradicting cycle of constraints.
+    pub fn solve(&self) -> Either<U> {
+        let source = self.size + 1;
+        let mut graph = self.mk_graph(false);
+        for v in 0..=self.size {
+            graph.add_edge(source,v,U::zero());
+        }
+        match bellman_ford::solve(&graph,source) {
+            bellman_ford::Either::Shortest(tree) => {
+                let zero = tree.dist(self.size).unwrap();
+                Either::Feasible((0..self.size).map(|v| tree.dist(v).unwrap() - zero).collect())
+            }
+            bellman_ford::Either::NegCircuit(circuit) => Either::Conflict(self.to_constraints(&circuit)),
+        }
+    }
+
+    // Componentwise greatest assignment, None for variables with no upper bound.
+    pub fn maximize(&self) -> Either<Option<U>> {
+        self.tightest(false)
+    }
+
+    // Componentwise smallest assignment, None for variables with no lower bound.
+    pub fn minimize(&self) -> Either<Option<U>> {
+        self.tightest(true)
+    }
+
+    fn tightest(&self, reverse: bool) -> Either<Option<U>> {
+        if let Either::Conflict(conflict) = self.solve() {
+            return Either::Conflict(conflict);
+        }
+        match bellman_ford::solve(&self.mk_graph(reverse),self.size) {
+            bellman_ford::Either::Shortest(tree) => {
+                Either::Feasible((0..self.size).map(|v| tree.dist(v).map(|d| if reverse { -d } else { d })).collect())
+            }
+            bellman_ford::Either::NegCircuit(_) => unreachable!(),
+        }
+    }
+
+    fn to_constraints(&self, circuit: &[(usize,usize)]) -> Vec<usize> {
+        let mut tightest: BTreeMap<(usize,usize),usize> = BTreeMap::new();
+        for (i, &(u,v,c)) in self.constraints.iter().enumerate() {
+            let entry = tightest.entry((u,v)).or_insert(i);
+            if c < self.constraints[*entry].2 {
+                *entry = i;
+            }
+        }
+        circuit.iter().map(|edge| tightest[edge]).collect()
+    }
+}
+
+
+#[test]
+fn test1() {
+    let mut problem = DifferenceConstraints::new(3);
+    problem.add(0,1,3);
+    problem.add(1,2,-2);
+    problem.add(0,2,4);
+    problem.lower(0,1);
+    match problem.solve() {
+        Either::Feasible(x) => {
+            assert!(x[1] - x[0] <= 3);
+            assert!(x[2] - x[1] <= -2);
+            assert!(x[2] - x[0] <= 4);
+            assert!(x[0] >= 1);
+        }
+        Either::Conflict(_) => unreachable!(),
+    }
+    assert_eq!(problem.minimize(),Either::Feasible(vec![Some(1),None,None]));
+    problem.upper(2,10);
+    assert_eq!(problem.maximize(),Either::Feasible(vec![None,None,Some(10)]));
+    assert_eq!(problem.minimize(),Either::Feasible(vec![Some(1),None,None]));
+    problem.upper(0,5);
+    assert_eq!(problem.maximize(),Either::Feasible(vec![Some(5),Some(8),Some(6)]));
+}
+
+#[test]
+fn test2() {
+    let mut problem = DifferenceConstraints::new(3);
+    problem.add(0,1,1);
+    problem.add(1,2,1);
+    problem.add(1,2,5);
+    problem.add(2,0,-3);
+    problem.upper(0,7);
+    match problem.solve() {
+        Either::Conflict(mut conflict) => {
+            conflict.sort();
+            assert_eq!(conflict,vec![0,1,3]);
+        }
+        Either::Feasible(_) => unreachable!(),
+    }
+}
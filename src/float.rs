@@ -0,0 +1,181 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Rem, Sub, SubAssign};
+use num::{FromPrimitive, Num, One, Signed, Zero};
+
+pub const EPS: f64 = 1e-9;
+
+// f64 with a total order: f64::total_cmp, except that every NaN (whatever its sign) is equal
+// to the others and greater than everything else, so it is the worst distance, and that
+// -0.0 == 0.0 as is_zero says. Comparisons are exact, Tolerant::improves ignores differences
+// below EPS.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct Float(pub f64);
+
+// improves(a, b) tells whether a is worth taking over b as a smaller distance or a leftover
+// capacity: a < b for exact types, a < b by more than the rounding error for Float.
+pub trait Tolerant: PartialOrd + Copy {
+    fn improves(self, other: Self) -> bool {
+        self < other
+    }
+}
+
+macro_rules! impl_exact {
+    ($($t:ty),*) => { $(impl Tolerant for $t {})* };
+}
+
+impl_exact!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl Tolerant for Float {
+    // smaller by more than EPS relative to their magnitude
+    fn improves(self, other: Float) -> bool {
+        let (a, b) = (self.0, other.0);
+        if a.is_finite() && b.is_finite() {
+            a < b - EPS * a.abs().max(b.abs()).max(1.0)
+        } else {
+            self < other
+        }
+    }
+}
+
+impl Ord for Float {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0.is_nan(), other.0.is_nan()) {
+            (false, false) if self.0 == other.0 => Ordering::Equal,
+            (false, false) => self.0.total_cmp(&other.0),
+            (a, b) => a.cmp(&b),
+        }
+    }
+}
+
+impl PartialOrd for Float {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Float {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Float {}
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<f64> for Float {
+    fn from(x: f64) -> Self {
+        Float(x)
+    }
+}
+
+impl Add for Float {
+    type Output = Float;
+    fn add(self, other: Float) -> Float {
+        Float(self.0 + other.0)
+    }
+}
+
+impl Sub for Float {
+    type Output = Float;
+    fn sub(self, other: Float) -> Float {
+        Float(self.0 - other.0)
+    }
+}
+
+impl Mul for Float {
+    type Output = Float;
+    fn mul(self, other: Float) -> Float {
+        Float(self.0 * other.0)
+    }
+}
+
+impl Div for Float {
+    type Output = Float;
+    fn div(self, other: Float) -> Float {
+        Float(self.0 / other.0)
+    }
+}
+
+impl Rem for Float {
+    type Output = Float;
+    fn rem(self, other: Float) -> Float {
+        Float(self.0 % other.0)
+    }
+}
+
+impl Neg for Float {
+    type Output = Float;
+    fn neg(self) -> Float {
+        Float(-self.0)
+    }
+}
+
+impl AddAssign for Float {
+    fn add_assign(&mut self, other: Float) {
+        self.0 += other.0;
+    }
+}
+
+impl SubAssign for Float {
+    fn sub_assign(&mut self, other: Float) {
+        self.0 -= other.0;
+    }
+}
+
+impl Zero for Float {
+    fn zero() -> Self {
+        Float(0.0)
+    }
+    fn is_zero(&self) -> bool {
+        self.0 == 0.0
+    }
+}
+
+impl One for Float {
+    fn one() -> Self {
+        Float(1.0)
+    }
+}
+
+impl Num for Float {
+    type FromStrRadixErr = <f64 as Num>::FromStrRadixErr;
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        f64::from_str_radix(s, radix).map(Float)
+    }
+}
+
+impl Signed for Float {
+    fn abs(&self) -> Self {
+        Float(self.0.abs())
+    }
+    fn abs_sub(&self, other: &Self) -> Self {
+        if *self <= *other { Float::zero() } else { *self - *other }
+    }
+    fn signum(&self) -> Self {
+        if self.is_zero() { Float::zero() } else { Float(self.0.signum()) }
+    }
+    fn is_positive(&self) -> bool {
+        *self > Float::zero()
+    }
+    fn is_negative(&self) -> bool {
+        *self < Float::zero()
+    }
+}
+
+impl FromPrimitive for Float {
+    fn from_i64(n: i64) -> Option<Self> {
+        Some(Float(n as f64))
+    }
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(Float(n as f64))
+    }
+    fn from_f64(n: f64) -> Option<Self> {
+        Some(Float(n))
+    }
+}
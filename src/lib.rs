@@ -2,4 +2,8 @@ pub mod graph;
 pub mod shortest_path;
 pub mod max_flow;
 pub mod min_cost_flow;
+pub mod float;
+pub mod modular;
+pub mod difference_constraints;
+pub mod traversal;
 pub mod prelude;
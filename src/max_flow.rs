@@ -35,3 +35,18 @@ fn test2() {
     let xx: BTreeSet<(usize,i64)> = problem.neighbors(0).collect();
     assert_eq!(xx.into_iter().fold(0, |x, (_, y)| x + y),11)
 }
+
+#[test]
+fn test_float() {
+    use crate::graph::*;
+    use crate::float::Float;
+    let mut graph = MatGraph::new(4);
+    graph.add_edge(0,1,Float(0.1));
+    graph.add_edge(0,2,Float(0.2));
+    graph.add_edge(1,3,Float(0.2));
+    graph.add_edge(2,3,Float(0.15));
+    graph.add_edge(1,2,Float(0.05));
+    let problem = dinic::solve(&graph,0,3);
+    let flow = problem.get(1,3).unwrap() + problem.get(2,3).unwrap();
+    assert_eq!(flow,Float(0.25));
+}
@@ -1,5 +1,6 @@
 use num::Zero;
 use std::ops::{Add,AddAssign,SubAssign,Sub};
+use crate::float::Tolerant;
 use crate::graph::*;
 use std::mem::swap;
 use std::cmp::min;
@@ -11,7 +12,7 @@ pub fn solve<A,U>(
     t: usize,
 ) -> A
 where A: AccGraph<Value = U>,
-    U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign,
+    U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign + Tolerant,
 {
     let size = graph.size();
     let mut flow: A = graph.clone();
@@ -35,7 +36,7 @@ fn mk_level_graph<A,U>(
     t: usize,
 ) -> Option<A>
 where A: AccGraph<Value = U>,
-      U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign,
+      U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign + Tolerant,
 {
     let mut lvl = A::new(res.size());
     let mut now = vec![s];
@@ -78,7 +79,7 @@ fn mk_blocking_flow<A,U>(
     t: usize,
 ) -> A
 where A: AccGraph<Value = U>,
-      U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign,
+      U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign + Tolerant,
 {
     let len = level_graph.size();
     let mut ret = A::new(len);
@@ -112,7 +113,7 @@ where A: AccGraph<Value = U>,
                 let minus = |x| {
                     match x {
                         Some(val) => {
-                            if val <= g {
+                            if !g.improves(val) {
                                 None
                             } else {
                                 Some(val-g)
@@ -141,7 +142,7 @@ fn augment_along_blocking<A,U>(
     graph: &A
 )
 where A: AccGraph<Value = U>,
-      U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign,
+      U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign + Tolerant,
 {
     for from in 0..blocking_flow.size() {
         for (to,g) in blocking_flow.neighbors(from) {
@@ -154,7 +155,7 @@ where A: AccGraph<Value = U>,
             let minus = |x| {
                 match x {
                     Some(val) => {
-                        if val <= g {
+                        if !g.improves(val) {
                             None
                         } else {
                             Some(val-g)
@@ -1,6 +1,7 @@
 use num::Zero;
 use std::ops::{Add,AddAssign,SubAssign,Sub};
 use std::collections::VecDeque;
+use crate::float::Tolerant;
 use crate::graph::AccGraph;
 
 pub fn solve<A,U>(
@@ -9,7 +10,7 @@ pub fn solve<A,U>(
     t: usize,
 ) -> A
 where A: AccGraph<Value = U>,
-    U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign,
+    U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign + Tolerant,
 {
     let size = graph.size();
     let mut flow: A = graph.clone();
@@ -32,7 +33,7 @@ fn bfs<A,U>(
     t: usize,
 ) -> Option<(Vec<(usize, usize)>, U)>
 where A: AccGraph<Value = U>,
-      U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign,
+      U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign + Tolerant,
 {
     let mut que = VecDeque::new();
     let mut arrived = vec![None; size];
@@ -75,7 +76,7 @@ fn augment_along_pass<A,U>(
     graph: &A,
 )
 where A: AccGraph<Value = U>,
-      U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign,
+      U: Copy + Ord + Add + Sub<Output = U> + Zero + AddAssign + SubAssign + Tolerant,
 {
         let plus = |x| {
             match x {
@@ -86,7 +87,7 @@ where A: AccGraph<Value = U>,
         let minus = |x| {
             match x {
                 Some(val) => {
-                    if val <= g {
+                    if !g.improves(val) {
                         None
                     } else {
                         Some(val-g)
@@ -1,3 +1,4 @@
+use crate::float::Tolerant;
 use crate::graph::*;
 use std::fmt::Debug;
 use std::ops::{Add,AddAssign,Sub,SubAssign};
@@ -12,7 +13,7 @@ pub struct MinCostFlow<A,U> {
 
 impl<A,U> MinCostFlow<A,U>
     where  A: AccGraph<Value = (U,U)>,
-           U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed,
+           U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed + Tolerant,
 {
     pub fn new(graph: A, b: Vec<U>) -> Self {
         MinCostFlow {
@@ -49,7 +50,7 @@ pub struct MinCostFlowST<A,U> {
 
 impl<A,U> MinCostFlowST<A,U>
     where  A: AccGraph<Value = (U,U)>,
-           U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed,
+           U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed + Tolerant,
 {
     pub fn new(graph: A, source: usize, sink: usize, amount: U) -> Self {
         MinCostFlowST {
@@ -179,6 +180,24 @@ fn test3() {
     }
 }
 
+#[test]
+fn test_supplies() {
+    use crate::graph::*;
+    // several supply vertices share the super source, whose edges must point out of it
+    let mut graph = MapGraph::new(4);
+    graph.add_edge(0,2,(5,1));
+    graph.add_edge(1,2,(5,2));
+    graph.add_edge(2,3,(5,1));
+    graph.add_edge(1,3,(1,5));
+    let (cost, flow): (i64,MapGraph<i64>) = MinCostFlow::new(graph.clone(),vec![3,2,0,-5]).min_cost_flow().unwrap();
+    assert_eq!(cost,12);
+    assert_eq!(flow.size(),4);
+    assert_eq!((flow.get(0,2),flow.get(1,2),flow.get(2,3),flow.get(1,3)),(Some(3),Some(2),Some(5),Some(0)));
+    graph.add_edge(0,1,(9,0));
+    let problem: Option<(i64,MatGraph<i64>)> = MinCostFlow::new(graph,vec![9,0,0,-5]).min_cost_flow();
+    assert!(problem.is_none());
+}
+
 /*
 #[test]
 fn test3() {
@@ -207,4 +226,28 @@ fn test3() {
         }
     }
 }
-*/
\ No newline at end of file
+*/
+#[test]
+fn test_float() {
+    use crate::graph::*;
+    use crate::float::Float;
+    let mut graph = MapGraph::new(5);
+    graph.add_edge(0,1,(Float(10.0),Float(0.2)));
+    graph.add_edge(0,2,(Float(2.0),Float(0.4)));
+    graph.add_edge(1,2,(Float(6.0),Float(0.6)));
+    graph.add_edge(1,3,(Float(6.0),Float(0.2)));
+    graph.add_edge(3,2,(Float(3.0),Float(0.3)));
+    graph.add_edge(2,4,(Float(5.0),Float(0.2)));
+    graph.add_edge(3,4,(Float(8.0),Float(0.6)));
+    let problem: Option<(Float,MapGraph<Float>)> = MinCostFlowST::new(graph,0,4,Float(9.0)).min_cost_flow();
+    assert_eq!(problem.unwrap().0,Float(8.0));
+
+    // the amount is 0.30000000000000004, what is left after sending 0.1 and 0.2 is rounding
+    let mut graph = MapGraph::new(4);
+    graph.add_edge(0,1,(Float(0.1),Float(1.0)));
+    graph.add_edge(0,2,(Float(0.2),Float(2.0)));
+    graph.add_edge(1,3,(Float(1.0),Float(0.0)));
+    graph.add_edge(2,3,(Float(1.0),Float(0.0)));
+    let problem: Option<(Float,MapGraph<Float>)> = MinCostFlowST::new(graph,0,3,Float(0.1) + Float(0.2)).min_cost_flow();
+    assert!((problem.unwrap().0 - Float(0.5)).0.abs() < 1e-12);
+}
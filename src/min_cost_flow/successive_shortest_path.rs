@@ -1,4 +1,5 @@
-use crate::shortest_path::{bellman_ford, dijkstra};
+use crate::shortest_path::{bellman_ford, dijkstra, ShortestPathTree};
+use crate::float::Tolerant;
 use crate::graph::*;
 use num::{Signed, Zero};
 use std::fmt::Debug;
@@ -12,58 +13,25 @@ pub fn solve_st<A,C,U>(
 ) -> Option<C>
     where A: AccGraph<Value = (U,U)>,
           C: AccGraph<Value = U>,
-          U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed,
+          U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed + Tolerant,
 {
     let size = graph.size();
     let mut flow: C = Graph::new(size);
-    let mut g_f_pot: C = Graph::new(size);
+    let mut g_f_cost: C = Graph::new(size);
     let mut g_f_cap: C = Graph::new(size);
     for from in 0..size {
         for (to, (cap, cost)) in graph.neighbors(from) {
             flow.add_edge(from,to, U::zero());
-            g_f_pot.add_edge(from,to,cost);
+            g_f_cost.add_edge(from,to,cost);
             g_f_cap.add_edge(from,to, cap);
         }
     }
-    let mut potential = {
-        match bellman_ford::solve(&g_f_pot, source) {
-            bellman_ford::Either::Shortest(vec) => {
-                let mut ret = vec![U::zero(); size];
-                for i in 0..size {
-                    ret[i] = vec[i].unwrap().0;
-                }
-                ret
-            }
-            _ => unreachable!(),
-        }
-    };
-    for from in 0..size {
-        for (to, (_, cost)) in graph.neighbors(from) {
-            g_f_pot.add_edge(from,to, cost + potential[from] - potential[to]);
-        }
-    }
+    let mut potential = bellman_ford::feasible_potential(&g_f_cost).unwrap();
     let mut now = amount;
-    while now > U::zero() {
-        let vec = dijkstra::solve(&g_f_pot, source);
-        if vec[sink].is_none() {
-            return None
-        }
-        let vec = vec
-            .iter()
-            .map(|x| x.unwrap())
-            .collect::<Vec<(U, usize)>>();
-        let pass = {
-            let mut pass = Vec::new();
-            let mut to = sink;
-            let mut from = vec[to].1;
-            while to != source {
-                pass.push((from, to));
-                to = from;
-                from = vec[from].1;
-            }
-            pass.reverse();
-            pass
-        };
+    // what is left within the tolerance of U is a rounding error, not a missing amount
+    while U::zero().improves(now) {
+        let tree = dijkstra::solve_with_potential(&g_f_cost, source, &potential);
+        let pass = tree.edges_to(sink)?;
         let gamma = {
             let mut gamma = now;
             for &(from, to) in &pass {
@@ -74,8 +42,8 @@ pub fn solve_st<A,C,U>(
             gamma
         };
         now -= gamma;
-        augment_along_pass(graph, &pass, &mut flow, &mut g_f_cap, gamma);
-        update_pot_and_g_f_pot(graph, &vec, &mut potential, &mut g_f_pot, &g_f_cap);
+        augment_along_pass(graph, &pass, &mut flow, &mut g_f_cap, &mut g_f_cost, gamma);
+        update_potential(&tree, &mut potential);
     }
     Some(flow)
 }
@@ -87,7 +55,7 @@ pub fn solve<A,C,U>(
 ) -> Option<C> 
 where A: AccGraph<Value = (U,U)>,
 C: AccGraph<Value = U>,
-U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed,
+U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed + Tolerant,
 {
     let size = graph.size();
     let b_sum = b
@@ -99,7 +67,7 @@ U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed
     graph.extend();
     for i in 0..size {
         if b[i] > U::zero() {
-            graph.add_edge(i,size,(b[i],U::zero()));
+            graph.add_edge(size,i,(b[i],U::zero()));
         }
     }
     for x in b.iter_mut() {
@@ -109,52 +77,22 @@ U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed
     }
     b.push(b_sum);    
     let mut flow: C = Graph::new(size+1);
-    let mut g_f_pot: C = Graph::new(size+1);
+    let mut g_f_cost: C = Graph::new(size+1);
     let mut g_f_cap: C = Graph::new(size+1);
     for from in 0..size + 1 {
         for (to, (cap, cost)) in graph.neighbors(from) {
             flow.add_edge(from,to, U::zero());
-            g_f_pot.add_edge(from,to,cost);
+            g_f_cost.add_edge(from,to,cost);
             g_f_cap.add_edge(from,to, cap);
         }
     }
-    let mut potential = {
-        match bellman_ford::solve(&g_f_pot, size) {
-            bellman_ford::Either::Shortest(vec) => {
-                let mut ret = vec![U::zero(); size + 1];
-                for i in 0..size + 1 {
-                    ret[i] = vec[i].unwrap().0;
-                }
-                ret
-            }
-            _ => unreachable!(),
-        }
-    };
-    for from in 0..size + 1 {
-        for (to, (_, cost)) in graph.neighbors(from) {
-            g_f_pot.add_edge(from,to, cost + potential[from] - potential[to]);
-        }
-    }
-    while b[size] > U::zero() {
-        match find(&g_f_pot, size, &b) {
+    let mut potential = bellman_ford::feasible_potential(&g_f_cost).unwrap();
+    while U::zero().improves(b[size]) {
+        match find(&g_f_cap, size, &b) {
             None => return None,
             Some(t) => {
-                let vec = dijkstra::solve(&g_f_pot, size)
-                    .iter()
-                    .map(|x| x.unwrap())
-                    .collect::<Vec<(U, usize)>>();
-                let pass = {
-                    let mut pass = Vec::new();
-                    let mut to = t;
-                    let mut from = vec[to].1;
-                    while to != size {
-                        pass.push((from, to));
-                        to = from;
-                        from = vec[from].1;
-                    }
-                    pass.reverse();
-                    pass
-                };
+                let tree = dijkstra::solve_with_potential(&g_f_cost, size, &potential);
+                let pass = tree.edges_to(t).unwrap();
                 let gamma = {
                     let mut gamma = if b[size] > -b[t] { -b[t] } else { b[size] };
                     for &(from, to) in &pass {
@@ -166,8 +104,8 @@ U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed
                 };
                 b[size] -= gamma;
                 b[t] += gamma;
-                augment_along_pass(&graph, &pass, &mut flow, &mut g_f_cap, gamma);
-                update_pot_and_g_f_pot(&graph, &vec, &mut potential, &mut g_f_pot, &g_f_cap);
+                augment_along_pass(&graph, &pass, &mut flow, &mut g_f_cap, &mut g_f_cost, gamma);
+                update_potential(&tree, &mut potential);
             }
         };
     }
@@ -176,20 +114,20 @@ U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed
 }
 
 fn find<C,U>(
-    g_f_pot: &C,
+    g_f_cap: &C,
     s: usize,
     b: &Vec<U>,
 ) -> Option<usize> 
 where C: AccGraph<Value = U>,
-U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed,
+U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed + Tolerant,
 {
-    let mut scanned = vec![false; g_f_pot.size()];
+    let mut scanned = vec![false; g_f_cap.size()];
     scanned[s] = true;
     let mut stack = vec![s];
     while let Some(from) = stack.pop() {
-        for (to, _) in g_f_pot.neighbors(from) {
+        for (to, _) in g_f_cap.neighbors(from) {
             if !scanned[to] {
-                if b[to] < U::zero() {
+                if b[to].improves(U::zero()) {
                     return Some(to);
                 }
                 scanned[to] = true;
@@ -206,11 +144,12 @@ fn augment_along_pass<A,C,U>(
     pass: &Vec<(usize, usize)>,
     flow: &mut C,
     g_f_cap: &mut C,
+    g_f_cost: &mut C,
     g: U,
 )
 where A: AccGraph<Value = (U,U)>,
 C: AccGraph<Value = U>,
-U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed,
+U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed + Tolerant,
 {
     let plus = |x| {
         match x {
@@ -221,7 +160,7 @@ U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed
     let minus = |x| {
         match x {
             Some(val) => {
-                if val <= g {
+                if !g.improves(val) {
                     None
                 } else {
                     Some(val-g)
@@ -240,32 +179,60 @@ U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed
             g_f_cap.modify(to,from,plus);
             g_f_cap.modify(from,to,minus);
         }
+        // keep the residual costs on exactly the edges with residual capacity
+        for &(u, v) in &[(from, to), (to, from)] {
+            let cost = if graph.is_edge(u,v) { graph.get(u,v).unwrap().1 } else { -graph.get(v,u).unwrap().1 };
+            let has = g_f_cap.is_edge(u,v);
+            g_f_cost.modify(u,v,|_| if has { Some(cost) } else { None });
+        }
     }
 }
 
-fn update_pot_and_g_f_pot<A,C,U>(
-    graph: &A,
-    vec: &Vec<(U, usize)>,
-    pot: &mut Vec<U>,
-    g_f_pot: &mut C,
-    g_f_cap: &C,
+// pot += d, where d are the reduced distances of the last search and the vertices it did not
+// reach get the largest distance found, max. For a residual edge (u, v) of reduced cost c >= 0:
+// - u and v reached: d(v) <= d(u) + c, so the new reduced cost c + d(u) - d(v) is still >= 0,
+// - u unreached, v reached: it becomes c + max - d(v) >= c, whereas adding nothing to pot[u]
+//   could make it negative and break Dijkstra once u is reached again,
+// - both unreached: unchanged, and u reached with v unreached cannot happen.
+// Augmenting only adds reverses of edges on a shortest path, whose reduced cost is 0.
+// So pot stays feasible on the whole residual graph, not only on the part reached so far.
+fn update_potential<U>(
+    tree: &ShortestPathTree<U>,
+    pot: &mut [U],
 )
-where A: AccGraph<Value = (U,U)>,
-C: AccGraph<Value = U>,
-U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed,
+where U: Debug + PartialOrd + Copy + Zero + Add + AddAssign + Sub + SubAssign + Signed + Tolerant,
 {
-    for i in 0..graph.size() {
-        pot[i] += vec[i].0;
+    let max = (0..pot.len())
+        .filter_map(|i| tree.dist(i))
+        .fold(U::zero(), |x, y| if y > x { y } else { x });
+    for (i, p) in pot.iter_mut().enumerate() {
+        *p += tree.dist(i).unwrap_or(max);
     }
-    let mut ret: C = Graph::new(graph.size());
-    for from in 0..graph.size() {
-        for (to, _) in g_f_cap.neighbors(from) {
-            if graph.is_edge(from,to) {
-                ret.add_edge(from,to,graph.get(from,to).unwrap().1+ pot[from] - pot[to]);
-            } else {
-                ret.add_edge(from,to,-graph.get(to,from).unwrap().1+ pot[from] - pot[to]);
-            }
+}
+
+
+#[test]
+fn test_update_potential() {
+    // 2 is unreachable from 0 in the first round and reachable in the second
+    let mut g: MapGraph<i64> = Graph::new(4);
+    g.add_edge(0,1,5);
+    g.add_edge(1,3,20);
+    g.add_edge(2,1,1);
+    g.add_edge(2,3,1);
+    let mut pot = bellman_ford::feasible_potential(&g).unwrap();
+    let tree = dijkstra::solve_with_potential(&g, 0, &pot);
+    assert!(!tree.is_reachable(2));
+    update_potential(&tree, &mut pot);
+    for from in 0..4 {
+        for (to, cost) in g.neighbors(from) {
+            assert!(cost + pot[from] - pot[to] >= 0);
         }
     }
-    std::mem::swap(&mut ret, g_f_pot);
+    g.add_edge(0,2,30);
+    assert!(30 + pot[0] - pot[2] >= 0);
+    let tree = dijkstra::solve_with_potential(&g, 0, &pot);
+    let expected = dijkstra::solve(&g, 0);
+    for v in 0..4 {
+        assert_eq!(tree.dist(v).map(|d| d - pot[0] + pot[v]), expected.dist(v));
+    }
 }
@@ -0,0 +1,81 @@
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+use num::{One, Zero};
+
+// residues modulo M, M must be positive. The value is always reduced, so the derived
+// comparisons are those of residues.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(x: u64) -> Self {
+        ModInt(x % M)
+    }
+
+    // the representative in 0..M
+    pub fn value(self) -> u64 {
+        self.0
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(x: u64) -> Self {
+        ModInt::new(x)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = ModInt<M>;
+    fn add(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt(((self.0 as u128 + other.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = ModInt<M>;
+    fn sub(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt(((self.0 as u128 + M as u128 - other.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = ModInt<M>;
+    fn mul(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt((self.0 as u128 * other.0 as u128 % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Zero for ModInt<M> {
+    fn zero() -> Self {
+        ModInt(0)
+    }
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const M: u64> One for ModInt<M> {
+    fn one() -> Self {
+        ModInt::new(1)
+    }
+}
+
+
+#[test]
+fn test1() {
+    type M = ModInt<7>;
+    assert_eq!(M::new(8),M::new(1));
+    assert_eq!(M::from(15).value(),1);
+    assert_eq!(M::new(5) + M::new(4),M::new(2));
+    assert_eq!(M::new(2) - M::new(5),M::new(4));
+    assert_eq!(M::new(3) * M::new(5),M::new(1));
+    assert!(M::new(14).is_zero());
+    let big = ModInt::<{ u64::MAX }>::new(u64::MAX - 1);
+    assert_eq!((big * big).value(),1);
+}
@@ -1,4 +1,7 @@
 pub use crate::graph::*;
 pub use crate::min_cost_flow::{MinCostFlow,MinCostFlowST};
+pub use crate::float::{Float,Tolerant};
+pub use crate::modular::ModInt;
+pub use crate::difference_constraints::DifferenceConstraints;
 // pub use crate::max_flow::MaxFlow;
 // pub use crate::shortest_path::ShortestPath;
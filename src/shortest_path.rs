@@ -1,5 +1,93 @@
 pub mod dijkstra;
 pub mod bellman_ford;
+pub mod johnson;
+pub mod spfa;
+pub mod astar;
+pub mod k_shortest;
+pub mod bfs;
+pub mod path_count;
+pub mod semiring;
+pub mod layered;
+pub mod min_mean_cycle;
+pub mod dag;
+pub mod dynamic;
+pub mod resource_constrained;
+pub mod pareto;
+pub mod time_dependent;
+pub mod matrix;
+
+// Which shortest path the *_with_tie_break functions keep when several have the same cost.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum TieBreak {
+    // lexicographically smallest vertex sequence from the start
+    Lexicographic,
+    // fewest edges, then the vertex discovered first by BFS
+    FewestEdges,
+}
+
+// What a visitor tells the *_with_visitor searches to do after an event.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Control {
+    Continue,
+    // after settled: skip the edges of the vertex, after relaxed: discard the improvement
+    Prune,
+    // stop the search and return what has been found so far
+    Break,
+}
+
+// Callbacks of the *_with_visitor searches, each defaults to Continue.
+// relaxed(from, to, d) reports that d improves on the distance of to, not_relaxed that it does not.
+pub trait Visitor<U> {
+    fn settled(&mut self, _v: usize, _dist: U) -> Control {
+        Control::Continue
+    }
+    fn relaxed(&mut self, _from: usize, _to: usize, _dist: U) -> Control {
+        Control::Continue
+    }
+    fn not_relaxed(&mut self, _from: usize, _to: usize, _dist: U) -> Control {
+        Control::Continue
+    }
+}
+
+impl<U> Visitor<U> for () {}
+
+// Distance and parent of every vertex, roots (the start vertices) have no parent.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct ShortestPathTree<U>(Vec<Option<(U, Option<usize>)>>);
+
+impl<U: Copy> ShortestPathTree<U> {
+    pub(crate) fn new(nodes: Vec<Option<(U, Option<usize>)>>) -> Self {
+        ShortestPathTree(nodes)
+    }
+    pub fn size(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_reachable(&self, v: usize) -> bool {
+        self.0[v].is_some()
+    }
+    pub fn dist(&self, v: usize) -> Option<U> {
+        self.0[v].map(|x| x.0)
+    }
+    pub fn parent(&self, v: usize) -> Option<usize> {
+        self.0[v].and_then(|x| x.1)
+    }
+    // vertices from the root to v
+    pub fn path_to(&self, v: usize) -> Option<Vec<usize>> {
+        self.0[v]?;
+        let mut ret = vec![v];
+        let mut to = v;
+        while let Some(from) = self.parent(to) {
+            ret.push(from);
+            to = from;
+        }
+        ret.reverse();
+        Some(ret)
+    }
+    // edges from the root to v
+    pub fn edges_to(&self, v: usize) -> Option<Vec<(usize, usize)>> {
+        self.path_to(v).map(|path| path.windows(2).map(|x| (x[0], x[1])).collect())
+    }
+}
 
 
 #[test]
@@ -28,12 +116,912 @@ fn test() {
     graph.add_edge(4,3,6);
     graph.add_edge(5,4,4);
 
-    let ans: Vec<(usize,usize)> = dijkstra::solve(&graph,0).iter().map(|x| x.unwrap()).collect();
+    let tree = dijkstra::solve(&graph,0);
+    let ans: Vec<usize> = (0..6).map(|v| tree.dist(v).unwrap()).collect();
+
+    assert_eq!(ans[0],0);
+    assert_eq!(ans[1],5);
+    assert_eq!(ans[2],4);
+    assert_eq!(ans[3],2);
+    assert_eq!(ans[4],6);
+    assert_eq!(ans[5],10);
+
+    assert_eq!(tree.parent(0),None);
+    assert_eq!(tree.parent(4),Some(2));
+    assert_eq!(tree.path_to(0),Some(vec![0]));
+    assert_eq!(tree.path_to(4),Some(vec![0,2,4]));
+    assert_eq!(tree.edges_to(4),Some(vec![(0,2),(2,4)]));
+
+    let mut graph = ListGraph::new(3);
+    graph.add_edge(0,1,1);
+    let tree = dijkstra::solve(&graph,0);
+    assert!(tree.is_reachable(1));
+    assert!(!tree.is_reachable(2));
+    assert_eq!(tree.path_to(2),None);
+}
+
+#[test]
+fn test_johnson() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,1);
+    graph.add_edge(0,2,5);
+    graph.add_edge(1,2,-2);
+    graph.add_edge(2,3,3);
+    graph.add_edge(3,1,4);
+
+    match johnson::solve(&graph) {
+        johnson::Either::Shortest(ret) => {
+            assert_eq!(ret[0].dist(3),Some(2));
+            assert_eq!(ret[0].path_to(3),Some(vec![0,1,2,3]));
+            assert_eq!(ret[3].dist(2),Some(2));
+            assert_eq!(ret[3].parent(2),Some(1));
+            assert!(!ret[1].is_reachable(0));
+            assert_eq!(ret[2].dist(2),Some(0));
+        }
+        _ => unreachable!(),
+    }
+
+    graph.add_edge(2,1,-1);
+    match johnson::solve(&graph) {
+        johnson::Either::NegCircuit(circuit) => {
+            let mut circuit = circuit;
+            circuit.sort();
+            assert_eq!(circuit,vec![(1,2),(2,1)]);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_spfa() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(5);
+    graph.add_edge(0,1,4);
+    graph.add_edge(0,2,1);
+    graph.add_edge(2,1,-2);
+    graph.add_edge(1,3,3);
+    graph.add_edge(2,3,5);
+    graph.add_edge(3,4,-1);
+
+    let expected = bellman_ford::solve(&graph,0);
+    for &slf in &[false,true] {
+        for &lll in &[false,true] {
+            let options = spfa::Options { slf, lll };
+            assert_eq!(spfa::solve_with_options(&graph,0,options),expected);
+        }
+    }
+
+    graph.add_edge(4,1,-3);
+    match spfa::solve(&graph,0) {
+        bellman_ford::Either::NegCircuit(circuit) => {
+            let mut circuit = circuit;
+            circuit.sort();
+            assert_eq!(circuit,vec![(1,3),(3,4),(4,1)]);
+        }
+        _ => unreachable!(),
+    }
+
+    // front * count would overflow here
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,4_000_000_000_000_000_000i64);
+    graph.add_edge(0,2,0);
+    graph.add_edge(0,3,0);
+    let options = spfa::Options { slf: false, lll: true };
+    assert_eq!(spfa::solve_with_options(&graph,0,options),bellman_ford::solve(&graph,0));
+
+    // d * count > sum and truncated division disagree for negative sums
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,-2);
+    graph.add_edge(0,2,-1);
+    graph.add_edge(1,3,-1);
+    graph.add_edge(2,3,-3);
+    for &slf in &[false,true] {
+        let options = spfa::Options { slf, lll: true };
+        assert_eq!(spfa::solve_with_options(&graph,0,options),bellman_ford::solve(&graph,0));
+    }
+
+    // rounding in the running sum used to rotate a single queued label forever
+    use crate::float::Float;
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,Float(11.281727490844805));
+    graph.add_edge(1,2,Float(12.392802121479987));
+    graph.add_edge(1,3,Float(8.240649071852506));
+    let options = spfa::Options { slf: false, lll: true };
+    assert_eq!(spfa::solve_with_options(&graph,0,options),bellman_ford::solve(&graph,0));
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,11.281727490844805);
+    graph.add_edge(1,2,12.392802121479987);
+    graph.add_edge(1,3,8.240649071852506);
+    assert_eq!(spfa::solve_with_options(&graph,0,options),bellman_ford::solve(&graph,0));
+
+    // a long path into a negative cycle is reported once it is found
+    let size = 20000;
+    let mut graph = ListGraph::new(size);
+    for v in 1..size {
+        graph.add_edge(v - 1,v,1);
+        graph.add_edge(0,v,v as i64);
+    }
+    graph.add_edge(size - 1,size - 3,-3);
+    match spfa::solve(&graph,0) {
+        bellman_ford::Either::NegCircuit(circuit) => assert_eq!(circuit.len(),3),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_bellman_ford_neg_inf() {
+    use crate::graph::*;
+    use bellman_ford::{Dist, Which};
+    let mut graph = ListGraph::new(6);
+    graph.add_edge(0,1,2);
+    graph.add_edge(1,2,-1);
+    graph.add_edge(2,1,-1);
+    graph.add_edge(2,3,1);
+    graph.add_edge(0,4,3);
+    graph.add_edge(4,3,1);
+
+    let dist = bellman_ford::solve_with_neg_inf(&graph,0);
+    assert_eq!(dist[0],Dist::Finite(0,None));
+    assert_eq!(dist[1],Dist::NegInf);
+    assert_eq!(dist[2],Dist::NegInf);
+    assert_eq!(dist[3],Dist::NegInf);
+    assert_eq!(dist[4],Dist::Finite(3,Some(0)));
+    assert_eq!(dist[5],Dist::Unreachable);
+
+    assert_eq!(bellman_ford::solve_with_goal(&graph,0,4),Which::Shortest(3,vec![(0,4)]));
+    assert_eq!(bellman_ford::solve_with_goal(&graph,0,3),Which::NegInf);
+    assert_eq!(bellman_ford::solve_with_goal(&graph,0,5),Which::Inaccseible);
+}
+
+#[test]
+fn test_astar() {
+    use crate::graph::*;
+    let (h, w) = (5, 6);
+    let wall = [(1,1),(1,2),(1,3),(2,3),(3,3),(3,1)];
+    let mut graph = ListGraph::new(h * w);
+    for i in 0..h {
+        for j in 0..w {
+            for &(di, dj) in &[(0,1),(1,0),(0,-1),(-1,0)] {
+                let (ni, nj) = (i as i64 + di, j as i64 + dj);
+                if ni < 0 || nj < 0 || ni >= h as i64 || nj >= w as i64 {
+                    continue;
+                }
+                let (ni, nj) = (ni as usize, nj as usize);
+                if !wall.contains(&(i,j)) && !wall.contains(&(ni,nj)) {
+                    graph.add_edge(i * w + j, ni * w + nj, 1);
+                }
+            }
+        }
+    }
+    let goal = 2 * w + 2;
+    let manhattan = |v: usize| ((v / w) as i64 - 2).abs() + ((v % w) as i64 - 2).abs();
+    let (val, pass) = astar::solve(&graph, 0, goal, manhattan).unwrap();
+    let (val_d, pass_d) = dijkstra::solve_with_goal(&graph, 0, goal).unwrap();
+    assert_eq!(val, val_d);
+    assert_eq!(pass.len(), pass_d.len());
+    assert_eq!(pass[0].1, goal);
+    assert_eq!(pass.last().unwrap().0, 0);
+    assert!(astar::solve(&graph, 0, 3 * w + 1, |_| 0).is_none());
+
+    // admissible but not consistent on (1, 2), so 2 has to be reopened
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,1);
+    graph.add_edge(0,2,3);
+    graph.add_edge(1,2,1);
+    graph.add_edge(2,3,3);
+    let h = [0, 4, 0, 0];
+    assert_eq!(astar::solve(&graph, 0, 3, |v| h[v]), Some((5, vec![(2,3),(1,2),(0,1)])));
+    assert_eq!(astar::solve_checked(&graph, 0, 3, |_| 0), Some((5, vec![(2,3),(1,2),(0,1)])));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "inconsistent heuristic on edge (1, 2)")]
+fn test_astar_checked() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,1);
+    graph.add_edge(0,2,3);
+    graph.add_edge(1,2,1);
+    graph.add_edge(2,3,3);
+    let h = [0, 4, 0, 0];
+    astar::solve_checked(&graph, 0, 3, |v| h[v]);
+}
+
+#[test]
+fn test_dijkstra_bidirectional() {
+    use crate::graph::*;
+    let edges = [(0,1,7),(0,2,9),(0,5,14),(1,2,10),(1,3,15),(2,3,11),(2,5,2),(3,4,6),(4,5,9),(5,6,1)];
+    let mut graph = ListGraph::new(8);
+    let mut reverse_graph = ListGraph::new(8);
+    for &(u, v, w) in &edges {
+        graph.add_edge(u,v,w);
+        reverse_graph.add_edge(v,u,w);
+    }
+    for s in 0..8 {
+        for t in 0..8 {
+            let ans = dijkstra::solve_with_goal(&graph,s,t);
+            let bi = dijkstra::solve_bidirectional(&graph,&reverse_graph,s,t);
+            assert_eq!(ans.as_ref().map(|x| x.0), bi.as_ref().map(|x| x.0));
+            if let Some((val, pass)) = bi {
+                assert_eq!(pass.iter().map(|&(u, v)| edges.iter().find(|e| (e.0, e.1) == (u, v)).unwrap().2).sum::<i32>(), val);
+                if s != t {
+                    assert_eq!(pass[0].1, t);
+                    assert_eq!(pass.last().unwrap().0, s);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_k_shortest() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(6);
+    graph.add_edge(0,1,3);
+    graph.add_edge(0,2,2);
+    graph.add_edge(1,3,4);
+    graph.add_edge(2,1,1);
+    graph.add_edge(2,3,2);
+    graph.add_edge(2,4,3);
+    graph.add_edge(3,4,2);
+    graph.add_edge(3,5,1);
+    graph.add_edge(4,5,2);
+
+    let paths = k_shortest::yen(&graph,0,5,3);
+    let costs: Vec<i64> = paths.iter().map(|x| x.0).collect();
+    assert_eq!(costs,vec![5,7,8]);
+    assert_eq!(paths[0].1,vec![(3,5),(2,3),(0,2)]);
+    assert_eq!(k_shortest::yen(&graph,0,5,100).len(),7);
+
+    // every walk goes from start to goal, has the reported cost and appears once
+    let check = |graph: &ListGraph<i64>, start: usize, goal: usize, walks: &[(i64, Vec<(usize,usize)>)]| {
+        for (i, (cost, pass)) in walks.iter().enumerate() {
+            let mut now = start;
+            let mut sum = 0;
+            for &(from, to) in pass.iter().rev() {
+                assert_eq!(from,now);
+                sum += graph.neighbors(from).find(|x| x.0 == to).unwrap().1;
+                now = to;
+            }
+            assert_eq!((now,sum),(goal,*cost));
+            assert!(walks[..i].iter().all(|x| x.1 != *pass));
+        }
+        walks.iter().map(|x| x.0).collect::<Vec<i64>>()
+    };
+    let walks = k_shortest::eppstein(&graph,0,5,8);
+    assert_eq!(check(&graph,0,5,&walks),vec![5,7,8,8,8,11,11]);
+    assert_eq!(walks[0].1,vec![(3,5),(2,3),(0,2)]);
+
+    graph.add_edge(5,0,1);
+    let walks = k_shortest::eppstein(&graph,0,5,8);
+    assert_eq!(check(&graph,0,5,&walks),vec![5,7,8,8,8,11,11,11]);
+    assert!(walks.iter().any(|x| x.1 == vec![(3,5),(2,3),(0,2),(5,0),(3,5),(2,3),(0,2)]));
+    let walks = k_shortest::eppstein(&graph,0,0,3);
+    assert_eq!(check(&graph,0,0,&walks),vec![0,6,8]);
+    assert_eq!(walks[0].1,vec![]);
+}
+
+#[test]
+fn test_path_count() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(6);
+    graph.add_edge(0,1,1);
+    graph.add_edge(0,2,1);
+    graph.add_edge(1,3,2);
+    graph.add_edge(2,3,2);
+    graph.add_edge(0,3,3);
+    graph.add_edge(3,4,1);
+    graph.add_edge(3,5,1);
+    graph.add_edge(4,5,1);
+    graph.add_edge(1,2,5);
+
+    let dist = dijkstra::solve(&graph,0);
+    let dag = path_count::mk_dag(&graph,&dist);
+    let edges: usize = (0..6).map(|v| dag.neighbors(v).count()).sum();
+    assert_eq!(edges,7);
+    let cnt: Vec<u64> = path_count::count(&dag,0);
+    assert_eq!(cnt,vec![1,1,1,3,3,3]);
+
+    let dist = bfs::solve(&graph,0);
+    assert_eq!((0..6).map(|v| dist.dist(v).unwrap()).collect::<Vec<_>>(),vec![0,1,1,1,2,2]);
+    let dag = path_count::mk_dag_by(&graph,&dist,|_| 1);
+    let cnt: Vec<u64> = path_count::count(&dag,0);
+    assert_eq!(cnt,vec![1,1,1,1,1,1]);
+}
+
+#[test]
+fn test_dijkstra_multi() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(7);
+    for i in 0..6 {
+        graph.add_edge(i,i+1,2);
+        graph.add_edge(i+1,i,2);
+    }
+    let (tree, nearest) = dijkstra::solve_multi(&graph,&[0,6]);
+    assert_eq!((tree.dist(0),tree.parent(0),nearest[0]),(Some(0),None,Some(0)));
+    assert_eq!((tree.dist(2),tree.parent(2),nearest[2]),(Some(4),Some(1),Some(0)));
+    assert_eq!((tree.dist(4),tree.parent(4),nearest[4]),(Some(4),Some(5),Some(6)));
+    assert_eq!(tree.path_to(4),Some(vec![6,5,4]));
+
+    let (tree, nearest) = dijkstra::solve_multi_with_offset(&graph,&[(0,0),(6,5)]);
+    assert_eq!((tree.dist(3),tree.parent(3),nearest[3]),(Some(6),Some(2),Some(0)));
+    assert_eq!((tree.dist(4),tree.parent(4),nearest[4]),(Some(8),Some(3),Some(0)));
+    assert_eq!((tree.dist(5),tree.parent(5),nearest[5]),(Some(7),Some(6),Some(6)));
+    assert_eq!((tree.dist(6),tree.parent(6),nearest[6]),(Some(5),None,Some(6)));
+}
+
+#[test]
+fn test_float() {
+    use crate::graph::*;
+    use crate::float::{Float, Tolerant};
+    let points = [(0.0,0.0),(1.0,0.0),(1.0,1.0),(0.0,1.0),(0.5,0.5)];
+    let mut graph = ListGraph::new(5);
+    for i in 0..5 {
+        for j in 0..5 {
+            if i != j {
+                let (dx, dy): (f64, f64) = (points[i].0 - points[j].0, points[i].1 - points[j].1);
+                graph.add_edge(i,j,Float((dx * dx + dy * dy).sqrt()));
+            }
+        }
+    }
+    let tree = dijkstra::solve(&graph,0);
+    assert_eq!(tree.dist(2),Some(Float(2f64.sqrt())));
+    match bellman_ford::solve(&graph,0) {
+        bellman_ford::Either::Shortest(x) => {
+            for v in 0..5 {
+                assert_eq!(x.dist(v),tree.dist(v));
+            }
+        }
+        _ => unreachable!(),
+    }
+    // a relaxation by a rounding error is exact, but Tolerant::improves does not count it
+    let mut graph = ListGraph::new(3);
+    graph.add_edge(0,2,Float(0.3 + 1e-12));
+    graph.add_edge(0,1,Float(0.1));
+    graph.add_edge(1,2,Float(0.2));
+    assert_eq!(dijkstra::solve(&graph,0).parent(2),Some(1));
+    assert!(!(Float(0.1) + Float(0.2)).improves(Float(0.3 + 1e-12)));
+    assert!(Float(0.1).improves(Float(0.2)));
+    assert_eq!(dijkstra::solve_semiring::<semiring::MinPlusEps<Float>,_>(&graph,0).parent(2),Some(0));
+    graph.add_edge(2,0,Float(-0.3 - 1e-12));
+    assert!(matches!(bellman_ford::solve(&graph,0),bellman_ford::Either::NegCircuit(_)));
+    assert!(matches!(bellman_ford::solve_semiring::<semiring::MinPlusEps<Float>,_>(&graph,0),bellman_ford::Either::Shortest(_)));
+
+    // NaN no longer panics inside the heap, and Float treats it as the worst distance
+    let mut graph = ListGraph::new(3);
+    graph.add_edge(0,1,f64::NAN);
+    graph.add_edge(0,2,1.0);
+    graph.add_edge(2,1,1.0);
+    assert_eq!(dijkstra::solve(&graph,0).dist(2),Some(1.0));
+    let zero = 0.0;
+    for &nan in &[f64::NAN,zero / zero,-f64::NAN] {
+        assert!(nan.is_nan());
+        for &x in &[f64::NEG_INFINITY,-1.0,-0.0,0.0,1e300,f64::INFINITY] {
+            assert!(Float(x) < Float(nan));
+        }
+        assert_eq!(Float(nan),Float(f64::NAN));
+        let mut graph = ListGraph::new(3);
+        graph.add_edge(0,1,Float(nan));
+        graph.add_edge(0,2,Float(1.0));
+        graph.add_edge(2,1,Float(1.0));
+        let tree = dijkstra::solve(&graph,0);
+        assert_eq!((tree.dist(1),tree.parent(1)),(Some(Float(2.0)),Some(2)));
+    }
+
+    // -0.0 and 0.0 are the same distance, both paths to 3 are shortest
+    assert_eq!(Float(-0.0),Float(0.0));
+    assert_eq!(Float(-0.0).cmp(&Float(0.0)),std::cmp::Ordering::Equal);
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,Float(-0.0));
+    graph.add_edge(1,3,Float(-0.0));
+    graph.add_edge(0,2,Float(0.0));
+    graph.add_edge(2,3,Float(0.0));
+    let dist = dijkstra::solve(&graph,0);
+    let cnt: Vec<u64> = path_count::count(&path_count::mk_dag(&graph,&dist),0);
+    assert_eq!(cnt,vec![1,1,1,2]);
+}
+
+#[test]
+fn test_semiring() {
+    use crate::graph::*;
+    use semiring::{MaxMin, MaxPlus, MaxTimes};
+    let mut graph = ListGraph::new(5);
+    graph.add_edge(0,1,4);
+    graph.add_edge(0,2,9);
+    graph.add_edge(1,3,8);
+    graph.add_edge(2,3,3);
+    graph.add_edge(2,1,7);
+    graph.add_edge(3,4,5);
+
+    let tree = dijkstra::solve_semiring::<MaxMin<i64>, _>(&graph,0);
+    assert_eq!(tree.dist(0),Some(i64::MAX));
+    assert_eq!(tree.dist(3),Some(7));
+    assert_eq!(tree.path_to(3),Some(vec![0,2,1,3]));
+    assert_eq!(tree.dist(4),Some(5));
+
+    match bellman_ford::solve_semiring::<MaxPlus<i64>, _>(&graph,0) {
+        bellman_ford::Either::Shortest(tree) => {
+            assert_eq!(tree.dist(4),Some(29));
+            assert_eq!(tree.path_to(4),Some(vec![0,2,1,3,4]));
+        }
+        _ => unreachable!(),
+    }
+
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,0.9);
+    graph.add_edge(1,3,0.9);
+    graph.add_edge(0,2,0.99);
+    graph.add_edge(2,3,0.8);
+    let tree = dijkstra::solve_semiring::<MaxTimes<f64>, _>(&graph,0);
+    assert!((tree.dist(3).unwrap() - 0.81).abs() < 1e-12);
+    assert_eq!(tree.parent(3),Some(1));
+
+    graph.add_edge(3,0,2.0);
+    match bellman_ford::solve_semiring::<MaxTimes<f64>, _>(&graph,0) {
+        bellman_ford::Either::NegCircuit(circuit) => assert_eq!(circuit.len(),3),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_layered() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,10);
+    graph.add_edge(1,3,10);
+    graph.add_edge(0,2,1);
+    graph.add_edge(2,3,30);
+
+    let layered = layered::Layered::new(4,1);
+    let lifted: ListGraph<i64> = layered.lift(&graph,|_, _, _| Some(0));
+    let tree = dijkstra::solve(&lifted,layered.index(0,0));
+    assert_eq!(layered.best(&tree,3),Some((1,1)));
+    assert_eq!(layered.path_to(&tree,3,1),Some(vec![(0,0),(2,0),(3,1)]));
+    assert_eq!(layered.path_to(&tree,3,0),Some(vec![(0,0),(1,0),(3,0)]));
+
+    let layered = layered::Layered::new(4,2);
+    let lifted: ListGraph<i64> = layered.lift(&graph,|_, _, w| Some(w / 2));
+    let tree = dijkstra::solve(&lifted,layered.index(0,0));
+    assert_eq!(layered.best(&tree,3),Some((10,2)));
+}
+
+#[test]
+fn test_min_mean_cycle() {
+    use crate::graph::*;
+    use num::rational::Ratio;
+    let mut graph = ListGraph::new(5);
+    graph.add_edge(0,1,1);
+    graph.add_edge(1,2,2);
+    graph.add_edge(2,0,4);
+    graph.add_edge(2,3,1);
+    graph.add_edge(3,4,2);
+    graph.add_edge(4,2,-1);
+    graph.add_edge(1,3,10);
+
+    let (mean, cycle) = min_mean_cycle::solve(&graph).unwrap();
+    assert_eq!(mean,Ratio::new(2,3));
+    let mut sorted = cycle.clone();
+    sorted.sort();
+    assert_eq!(sorted,vec![(2,3),(3,4),(4,2)]);
+    for i in 0..cycle.len() {
+        assert_eq!(cycle[i].1,cycle[(i + 1) % cycle.len()].0);
+    }
+
+    graph.add_edge(4,4,0);
+    assert_eq!(min_mean_cycle::solve(&graph).unwrap(),(Ratio::new(0,1),vec![(4,4)]));
+
+    let mut dag = ListGraph::new(3);
+    dag.add_edge(0,1,-5);
+    dag.add_edge(1,2,-5);
+    assert!(min_mean_cycle::solve(&dag).is_none());
+}
+
+#[test]
+fn test_dag() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(6);
+    graph.add_edge(0,1,5);
+    graph.add_edge(0,2,3);
+    graph.add_edge(1,3,6);
+    graph.add_edge(1,2,2);
+    graph.add_edge(2,4,4);
+    graph.add_edge(2,5,2);
+    graph.add_edge(2,3,7);
+    graph.add_edge(3,4,-1);
+    graph.add_edge(4,5,-2);
+
+    let order = dag::topological_sort(&graph).unwrap();
+    let mut position = [0; 6];
+    for (i, &v) in order.iter().enumerate() {
+        position[v] = i;
+    }
+    for from in 0..6 {
+        for (to, _) in graph.neighbors(from) {
+            assert!(position[from] < position[to]);
+        }
+    }
+
+    let tree = dag::solve(&graph,1).unwrap();
+    assert_eq!((1..6).map(|v| tree.dist(v).unwrap()).collect::<Vec<_>>(),vec![0,2,6,5,3]);
+    assert!(!tree.is_reachable(0));
+    let tree = dag::solve_longest(&graph,0).unwrap();
+    assert_eq!(tree.dist(5),Some(11));
+    assert_eq!(tree.path_to(5),Some(vec![0,1,2,3,4,5]));
+
+    graph.add_edge(4,1,1);
+    let cycle = dag::topological_sort(&graph).unwrap_err();
+    for i in 0..cycle.len() {
+        assert_eq!(cycle[i].1,cycle[(i + 1) % cycle.len()].0);
+    }
+    assert!(cycle.contains(&(4,1)));
+    assert!(dag::solve(&graph,0).is_err());
+}
+
+#[test]
+fn test_hop_limited() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,100);
+    graph.add_edge(1,2,100);
+    graph.add_edge(2,0,100);
+    graph.add_edge(1,3,600);
+    graph.add_edge(2,3,200);
+
+    let ret = bellman_ford::solve_hop_limited(&graph,0,1);
+    assert_eq!(ret.dist(3),None);
+    let ret = bellman_ford::solve_hop_limited(&graph,0,2);
+    assert_eq!(ret.dist(3),Some(700));
+    assert_eq!(ret.edges_to(3),Some(vec![(0,1),(1,3)]));
+    let ret = bellman_ford::solve_hop_limited(&graph,0,5);
+    assert_eq!(ret.dist(3),Some(400));
+    assert_eq!(ret.dist_at(2,3),Some(700));
+    assert_eq!(ret.edges_to(3),Some(vec![(0,1),(1,2),(2,3)]));
+    assert_eq!(ret.edges_to(0),Some(vec![]));
+
+    let ret = bellman_ford::solve_exact_hops(&graph,0,4);
+    assert_eq!(ret.dist(0),None);
+    assert_eq!(ret.dist(1),Some(400));
+    assert_eq!(ret.edges_to(1),Some(vec![(0,1),(1,2),(2,0),(0,1)]));
+    assert_eq!(ret.dist_at(3,0),Some(300));
+}
+
+#[test]
+fn test_dynamic() {
+    use crate::graph::*;
+    let edges = [(0,1,10),(1,2,10),(2,3,10),(0,3,50),(3,4,1),(4,0,7)];
+    let updates = [(0,2,5),(2,4,3),(1,3,1),(0,3,50),(4,2,0)];
+    let mut graph = MatGraph::new(5);
+    for &(u, v, w) in &edges {
+        graph.add_edge(u,v,w);
+    }
+    let mut sssp = dynamic::DynamicSssp::new(graph.clone(),0);
+    let mut apsp = dynamic::DynamicApsp::new(graph.clone());
+    for &(u, v, w) in &updates {
+        graph.add_edge(u,v,w);
+        sssp.add_edge(u,v,w);
+        apsp.add_edge(u,v,w);
+        for s in 0..5 {
+            let tree = dijkstra::solve(&graph,s);
+            for t in 0..5 {
+                assert_eq!(apsp.dist(s,t),tree.dist(t));
+                if s == 0 {
+                    assert_eq!(sssp.dist(t),tree.dist(t));
+                }
+            }
+        }
+    }
+    let path = sssp.tree().path_to(4).unwrap();
+    assert_eq!(path,vec![0,2,4]);
+    assert_eq!(apsp.graph().get(0,3),Some(50));
+}
+
+#[test]
+#[should_panic(expected = "increased")]
+fn test_dynamic_apsp_increase() {
+    use crate::graph::*;
+    let mut graph = MatGraph::new(3);
+    graph.add_edge(0,1,1);
+    graph.add_edge(1,2,1);
+    let mut apsp = dynamic::DynamicApsp::new(graph);
+    apsp.add_edge(0,1,5);
+}
+
+#[test]
+#[should_panic(expected = "increased")]
+fn test_dynamic_sssp_increase() {
+    use crate::graph::*;
+    let mut graph = MapGraph::new(3);
+    graph.add_edge(0,1,1);
+    let mut sssp = dynamic::DynamicSssp::new(graph,0);
+    sssp.add_edge(0,1,5);
+}
+
+#[test]
+fn test_dynamic_sssp_parallel() {
+    use crate::graph::*;
+    // a ListGraph keeps the lighter edge next to the heavier one
+    let mut graph = ListGraph::new(3);
+    graph.add_edge(0,1,1);
+    graph.add_edge(1,2,1);
+    let mut sssp = dynamic::DynamicSssp::new(graph,0);
+    sssp.add_edge(0,1,5);
+    assert_eq!((sssp.dist(1),sssp.dist(2)),(Some(1),Some(2)));
+    assert_eq!(sssp.graph().neighbors(0).count(),2);
+    sssp.add_edge(0,2,1);
+    assert_eq!((sssp.dist(1),sssp.dist(2)),(Some(1),Some(1)));
+}
+
+#[test]
+fn test_tie_break() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(6);
+    graph.add_edge(0,3,1);
+    graph.add_edge(0,2,1);
+    graph.add_edge(0,1,2);
+    graph.add_edge(3,1,1);
+    graph.add_edge(2,4,2);
+    graph.add_edge(1,4,1);
+    graph.add_edge(4,5,1);
+    graph.add_edge(3,5,3);
+
+    let lex = dijkstra::solve_with_tie_break(&graph,0,TieBreak::Lexicographic);
+    assert_eq!(lex.path_to(4),Some(vec![0,1,4]));
+    assert_eq!(lex.path_to(5),Some(vec![0,1,4,5]));
+    let few = dijkstra::solve_with_tie_break(&graph,0,TieBreak::FewestEdges);
+    assert_eq!(few.path_to(5),Some(vec![0,3,5]));
+    assert_eq!(few.dist(5),lex.dist(5));
+    let (val, pass) = dijkstra::solve_with_goal_tie_break(&graph,0,4,TieBreak::Lexicographic).unwrap();
+    assert_eq!((val, pass),(3,vec![(1,4),(0,1)]));
+
+    let lex = bfs::solve_with_tie_break(&graph,0,TieBreak::Lexicographic);
+    assert_eq!(lex.path_to(4),Some(vec![0,1,4]));
+    assert_eq!(lex.path_to(5),Some(vec![0,3,5]));
+
+    // equal values pop in increasing label order
+    use std::collections::BinaryHeap;
+    use std::cmp::Reverse;
+    let mut heap: BinaryHeap<_> = [3,1,2].iter().map(|&l| Reverse(dijkstra::Pair::new(l,0))).collect();
+    heap.push(Reverse(dijkstra::Pair::new(4,-1)));
+    let order: Vec<usize> = std::iter::from_fn(|| heap.pop().map(|x| x.0.label)).collect();
+    assert_eq!(order,vec![4,1,2,3]);
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,2,1);
+    graph.add_edge(0,1,1);
+    graph.add_edge(2,3,1);
+    graph.add_edge(1,3,1);
+    assert_eq!(dijkstra::solve(&graph,0).parent(3),Some(1));
+
+    // the search stops at the goal but still sees ties through zero-weight edges
+    let mut graph = ListGraph::new(5);
+    graph.add_edge(0,3,1);
+    graph.add_edge(0,2,1);
+    graph.add_edge(2,3,0);
+    graph.add_edge(0,1,5);
+    graph.add_edge(1,4,1);
+    let lex = dijkstra::solve_with_goal_tie_break(&graph,0,3,TieBreak::Lexicographic);
+    assert_eq!(lex,Some((1,vec![(2,3),(0,2)])));
+    let few = dijkstra::solve_with_goal_tie_break(&graph,0,3,TieBreak::FewestEdges);
+    assert_eq!(few,Some((1,vec![(0,3)])));
+    assert_eq!(dijkstra::solve_with_goal_tie_break(&graph,0,4,TieBreak::FewestEdges),Some((6,vec![(1,4),(0,1)])));
+    assert_eq!(dijkstra::solve_with_goal_tie_break(&graph,3,0,TieBreak::FewestEdges),None);
+}
+
+#[test]
+fn test_resource_constrained() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,(1,[10,1]));
+    graph.add_edge(1,3,(1,[10,1]));
+    graph.add_edge(0,2,(5,[1,5]));
+    graph.add_edge(2,3,(5,[1,5]));
+    graph.add_edge(0,3,(20,[1,1]));
+    graph.add_edge(1,2,(1,[1,1]));
+
+    assert_eq!(resource_constrained::solve(&graph,0,3,[100,100]),Some((2,[20,2],vec![(1,3),(0,1)])));
+    assert_eq!(resource_constrained::solve(&graph,0,3,[15,100]),Some((7,[12,7],vec![(2,3),(1,2),(0,1)])));
+    assert_eq!(resource_constrained::solve(&graph,0,3,[15,5]),Some((20,[1,1],vec![(0,3)])));
+    assert_eq!(resource_constrained::solve(&graph,0,3,[0,0]),None);
+
+    let mut graph = ListGraph::new(3);
+    graph.add_edge(0,1,(3,[4]));
+    graph.add_edge(0,1,(1,[9]));
+    graph.add_edge(1,2,(1,[1]));
+    assert_eq!(resource_constrained::solve(&graph,0,2,[5]).map(|x| x.0),Some(4));
+    assert_eq!(resource_constrained::solve(&graph,0,2,[10]).map(|x| x.0),Some(2));
+}
+
+#[test]
+fn test_pareto() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(5);
+    graph.add_edge(0,1,(1,10));
+    graph.add_edge(0,2,(4,2));
+    graph.add_edge(1,3,(1,10));
+    graph.add_edge(2,3,(4,2));
+    graph.add_edge(1,2,(1,1));
+    graph.add_edge(0,3,(3,30));
+    graph.add_edge(3,4,(1,1));
+
+    let frontier = pareto::solve(&graph,0);
+    assert_eq!(frontier.size(),5);
+    assert_eq!(frontier.costs(0),vec![(0,0)]);
+    assert_eq!(frontier.costs(2),vec![(2,11),(4,2)]);
+    assert_eq!(frontier.costs(3),vec![(2,20),(6,13),(8,4)]);
+    assert_eq!(frontier.path(3,0),vec![(0,1),(1,3)]);
+    assert_eq!(frontier.path(3,1),vec![(0,1),(1,2),(2,3)]);
+    assert_eq!(frontier.path(3,2),vec![(0,2),(2,3)]);
+    assert_eq!(frontier.costs(4),vec![(3,21),(7,14),(9,5)]);
+    assert_eq!(frontier.path(4,2),vec![(0,2),(2,3),(3,4)]);
+
+    let mut graph = ListGraph::new(3);
+    graph.add_edge(0,1,(1,1));
+    graph.add_edge(0,1,(1,1));
+    graph.add_edge(0,1,(2,1));
+    assert_eq!(pareto::solve(&graph,0).costs(1),vec![(1,1)]);
+    assert_eq!(pareto::solve(&graph,0).costs(2),vec![]);
+}
+
+#[test]
+fn test_time_dependent() {
+    use crate::graph::*;
+    let mut table = time_dependent::Timetable::new();
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,table.add(vec![(20,25),(5,10),(10,12)]));
+    graph.add_edge(1,2,table.add(vec![(11,30),(13,14)]));
+    graph.add_edge(0,2,table.add(vec![(0,40)]));
+    graph.add_edge(2,3,table.add(vec![(15,16),(50,51)]));
+    assert_eq!(table.arrival(0,6),Some(12));
+    assert_eq!(table.arrival(0,21),None);
+
+    let tree = table.solve(&graph,0,0);
+    assert_eq!(tree.dist(1),Some(10));
+    assert_eq!(tree.dist(2),Some(14));
+    assert_eq!(tree.dist(3),Some(16));
+    assert_eq!(tree.path_to(3),Some(vec![0,1,2,3]));
+
+    let tree = table.solve(&graph,0,11);
+    assert_eq!(tree.dist(1),Some(25));
+    assert_eq!(tree.dist(2),None);
+    assert_eq!(tree.dist(3),None);
+
+    // traffic lights: green during the first half of every period given as edge value
+    let mut graph = ListGraph::new(3);
+    graph.add_edge(0,1,(2,10));
+    graph.add_edge(1,2,(1,4));
+    graph.add_edge(0,2,(9,1));
+    let tree = time_dependent::solve(&graph,0,0,|_,_,(len,period): (u64,u64),t| {
+        let wait = if t % period < period / 2 { 0 } else { period - t % period };
+        Some(t + wait + len)
+    });
+    assert_eq!(tree.dist(1),Some(2));
+    assert_eq!(tree.dist(2),Some(5));
+}
+
+#[test]
+fn test_matrix() {
+    use crate::graph::*;
+    use crate::modular::ModInt;
+    use semiring::{MaxPlus, MinPlus};
+    let mut graph = MatGraph::new(3);
+    graph.add_edge(0,1,1);
+    graph.add_edge(1,2,2);
+    graph.add_edge(2,0,3);
+    graph.add_edge(1,0,10);
+    let mat = matrix::from_graph(&graph);
+
+    let shortest = matrix::power::<MinPlus<i64>>(&mat,2);
+    assert_eq!(shortest[0],vec![Some(11),None,Some(3)]);
+    assert_eq!(shortest[1],vec![Some(5),Some(11),None]);
+    assert_eq!(matrix::power::<MinPlus<i64>>(&mat,0),matrix::identity::<MinPlus<i64>>(3));
+    // the cheapest cycle 0 -> 1 -> 2 -> 0 costs 6
+    assert_eq!(matrix::power::<MinPlus<i64>>(&mat,3_000_000_000_000)[0][0],Some(6_000_000_000_000));
+    assert_eq!(matrix::power::<MaxPlus<i64>>(&mat,4)[0][0],Some(22));
+
+    let mut graph = ListGraph::new(2);
+    graph.add_edge(0,0,());
+    graph.add_edge(0,1,());
+    graph.add_edge(1,0,());
+    // Fibonacci numbers
+    let counts = matrix::counts::<u64,_>(&graph);
+    assert_eq!(matrix::power_counts(&counts,10)[0],vec![89,55]);
+    let counts = matrix::counts::<ModInt<1_000_000_007>,_>(&graph);
+    assert_eq!(matrix::power_counts(&counts,1_000_000_000_000_000_000)[0][1],ModInt::new(209_783_453));
+}
+
+#[test]
+fn test_visitor() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(5);
+    graph.add_edge(0,1,1);
+    graph.add_edge(0,2,4);
+    graph.add_edge(1,2,1);
+    graph.add_edge(2,3,1);
+    graph.add_edge(3,4,1);
+    graph.add_edge(1,4,10);
+
+    #[derive(Default)]
+    struct Stats {
+        settled: Vec<usize>,
+        relaxed: usize,
+        not_relaxed: usize,
+    }
+    impl Visitor<i64> for Stats {
+        fn settled(&mut self, v: usize, _: i64) -> Control {
+            self.settled.push(v);
+            Control::Continue
+        }
+        fn relaxed(&mut self, _: usize, _: usize, _: i64) -> Control {
+            self.relaxed += 1;
+            Control::Continue
+        }
+        fn not_relaxed(&mut self, _: usize, _: usize, _: i64) -> Control {
+            self.not_relaxed += 1;
+            Control::Continue
+        }
+    }
+    let mut stats = Stats::default();
+    assert_eq!(dijkstra::solve_with_visitor(&graph,0,&mut stats),dijkstra::solve(&graph,0));
+    assert_eq!(stats.settled,vec![0,1,2,3,4]);
+    assert_eq!((stats.relaxed,stats.not_relaxed),(6,0));
+
+    // stop once 2 is settled
+    struct Until(usize);
+    impl Visitor<i64> for Until {
+        fn settled(&mut self, v: usize, _: i64) -> Control {
+            if v == self.0 { Control::Break } else { Control::Continue }
+        }
+    }
+    let tree = dijkstra::solve_with_visitor(&graph,0,&mut Until(2));
+    assert_eq!(tree.dist(2),Some(2));
+    assert_eq!(tree.dist(3),None);
+    assert_eq!(tree.dist(4),Some(11));
+
+    // never leave through vertex 1
+    struct Avoid(usize);
+    impl Visitor<i64> for Avoid {
+        fn settled(&mut self, v: usize, _: i64) -> Control {
+            if v == self.0 { Control::Prune } else { Control::Continue }
+        }
+        fn relaxed(&mut self, from: usize, _: usize, _: i64) -> Control {
+            if from == self.0 { Control::Prune } else { Control::Continue }
+        }
+    }
+    let tree = dijkstra::solve_with_visitor(&graph,0,&mut Avoid(1));
+    assert_eq!(tree.dist(4),Some(6));
+    match bellman_ford::solve_with_visitor(&graph,0,&mut Avoid(1)) {
+        bellman_ford::Either::Shortest(bf) => assert_eq!(bf,tree),
+        bellman_ford::Either::NegCircuit(_) => unreachable!(),
+    }
+
+    let mut stats = Stats::default();
+    bellman_ford::solve_with_visitor(&graph,0,&mut stats);
+    assert_eq!(stats.settled,vec![0,1,2,3,4]);
+    assert_eq!(stats.relaxed,6);
+}
+
+#[test]
+fn test_potential() {
+    use crate::graph::*;
+    let mut graph = ListGraph::new(4);
+    graph.add_edge(0,1,4);
+    graph.add_edge(0,2,1);
+    graph.add_edge(2,1,-2);
+    graph.add_edge(1,3,3);
+    graph.add_edge(2,3,5);
+
+    let potential = bellman_ford::feasible_potential(&graph).unwrap();
+    for from in 0..4 {
+        for (to, w) in graph.neighbors(from) {
+            assert!(w + potential[from] - potential[to] >= 0);
+        }
+    }
+    let tree = dijkstra::solve_with_potential(&graph,0,&potential);
+    let dist: Vec<_> = (0..4).map(|v| tree.dist(v).unwrap() - potential[0] + potential[v]).collect();
+    assert_eq!(dist,vec![0,-1,1,2]);
+    assert_eq!(tree.path_to(3),Some(vec![0,2,1,3]));
 
-    assert_eq!(ans[0].0,0);
-    assert_eq!(ans[1].0,5);
-    assert_eq!(ans[2].0,4);
-    assert_eq!(ans[3].0,2);
-    assert_eq!(ans[4].0,6);
-    assert_eq!(ans[5].0,10)
-}
\ No newline at end of file
+    graph.add_edge(1,2,1);
+    assert_eq!(bellman_ford::feasible_potential(&graph),None);
+}
@@ -0,0 +1,76 @@
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+use std::ops::Add;
+use num::Zero;
+use crate::graph::Graph;
+use crate::shortest_path::dijkstra::Pair;
+use crate::shortest_path::ShortestPathTree;
+
+// heuristic(v) must never overestimate the distance from v to goal. It need not be consistent,
+// a vertex reached again by a shorter path is reopened, which never happens when it is.
+pub fn solve<U, A, H>(
+    graph: &A,
+    start: usize,
+    goal: usize,
+    heuristic: H,
+) -> Option<(U,Vec<(usize,usize)>)>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Zero + PartialOrd + Copy,
+      H: Fn(usize) -> U,
+{
+    run(graph, start, goal, heuristic, false)
+}
+
+// Same as solve, but debug builds also check heuristic(u) <= w + heuristic(v) on every relaxed edge.
+pub fn solve_checked<U, A, H>(
+    graph: &A,
+    start: usize,
+    goal: usize,
+    heuristic: H,
+) -> Option<(U,Vec<(usize,usize)>)>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Zero + PartialOrd + Copy,
+      H: Fn(usize) -> U,
+{
+    run(graph, start, goal, heuristic, true)
+}
+
+fn run<U, A, H>(
+    graph: &A,
+    start: usize,
+    goal: usize,
+    heuristic: H,
+    check: bool,
+) -> Option<(U,Vec<(usize,usize)>)>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Zero + PartialOrd + Copy,
+      H: Fn(usize) -> U,
+{
+    let size = graph.size();
+    let mut potential = vec![None; size];
+    potential[start] = Some((U::zero(), None));
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse(Pair::new(start, heuristic(start))));
+    while let Some(Reverse(pair)) = heap.pop() {
+        let g = potential[pair.label].unwrap().0;
+        let h = heuristic(pair.label);
+        // an entry pushed before the last improvement of its vertex
+        if pair.value > g + h {
+            continue;
+        }
+        if pair.label == goal {
+            let tree = ShortestPathTree::new(potential);
+            let mut pass = tree.edges_to(goal).unwrap();
+            pass.reverse();
+            return Some((tree.dist(goal).unwrap(),pass))
+        }
+        for (v, weight) in graph.neighbors(pair.label) {
+            if potential[v].is_none() || potential[v].map(|x| x.0) > Some(g + weight) {
+                debug_assert!(!check || h <= weight + heuristic(v), "inconsistent heuristic on edge ({}, {})", pair.label, v);
+                potential[v] = Some((g + weight, Some(pair.label)));
+                heap.push(Reverse(Pair::new(v, g + weight + heuristic(v))));
+            }
+        }
+    }
+    None
+}
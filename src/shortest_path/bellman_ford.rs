@@ -1,6 +1,8 @@
 use std::ops::Add;
 use num::Zero;
-use crate::graph::Graph;
+use crate::graph::{Graph, ListGraph};
+use crate::shortest_path::{Control, ShortestPathTree, Visitor};
+use crate::shortest_path::semiring::{MinPlus, Semiring};
 
 #[derive(Debug,Clone,PartialEq,Eq)]
 pub enum Which<U> {
@@ -9,130 +11,309 @@ pub enum Which<U> {
     NegInf
 }
 
-/*
-pub fn solve_with_goal<A,B,U>(graph: &impl Graph<Item = U, Iterator = B>, start: usize, goal: usize) -> Which<U>
-    where U: Add + Zero + PartialOrd + Copy,
-          B: Iterator<Item = (usize,U)>
-{
-    use Which::*; 
-    let size = graph.len();
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Dist<U> {
+    Finite(U, Option<usize>),
+    Unreachable,
+    NegInf,
+}
+
+pub fn solve_with_goal<U: Add + Zero + PartialOrd + Copy, A: Graph<Value = U>>(
+    graph: &A,
+    start: usize,
+    goal: usize
+) -> Which<U> {
+    use Which::*;
+    let dist = solve_with_neg_inf(graph, start);
+    match dist[goal] {
+        Dist::Finite(val, _) => {
+            let tree = ShortestPathTree::new(dist.iter().map(|x| match *x {
+                Dist::Finite(d, from) => Some((d, from)),
+                _ => None,
+            }).collect());
+            let mut pass = tree.edges_to(goal).unwrap();
+            pass.reverse();
+            Shortest(val,pass)
+        }
+        Dist::Unreachable => Inaccseible,
+        Dist::NegInf => NegInf,
+    }
+}
+
+// Unlike solve, keeps the distances when a negative circuit exists and marks every vertex
+// reachable from one as NegInf.
+pub fn solve_with_neg_inf<U: Add + Zero + PartialOrd + Copy, A: Graph<Value = U>>(
+    graph: &A,
+    start: usize
+) -> Vec<Dist<U>> {
+    let size = graph.size();
     let mut ret = vec![None; size];
-    ret[start] = Some((U::zero(), 0));
+    ret[start] = Some((U::zero(), None));
+    let mut converged = false;
     for _ in 0..size {
         let mut flag = false;
         for from in 0..size {
-            for (to, weight) in graph.iter(from) {
-                if !ret[from].is_none()
+            for (to,weight) in graph.neighbors(from) {
+                if ret[from].is_some()
                     && (ret[to].is_none()
                         || ret[to].map(|(x, _)| x) > ret[from].map(|(x, _)| x + weight))
                 {
-                    ret[to] = Some((ret[from].unwrap().0 + weight, from));
+                    ret[to] = Some((ret[from].unwrap().0 + weight, Some(from)));
                     flag = true;
                 }
             }
         }
         if !flag {
-            if let Some((val,from)) = ret[goal] {
-                let mut from = from;
-                let mut to = goal;
-                let mut pass = Vec::new();
-                while to != start {
-                    pass.push((from,to));
-                    to = from;
-                    from = ret[from].unwrap().1;
-                }
-                return Shortest(val,pass)
-            } else {
-                return Inaccseible
-            }
+            converged = true;
+            break;
         }
     }
     let mut neg = vec![false; size];
-    for _ in 0..size {
+    if !converged {
+        let mut stack = Vec::new();
         for from in 0..size {
-            for (to, weight) in graph.iter(from) {
-                if !ret[from].is_none()
-                    && (ret[to].is_none()
-                        || ret[to].map(|(x, _)| x) > ret[from].map(|(x, _)| x + weight))
+            for (to,weight) in graph.neighbors(from) {
+                if ret[from].is_some()
+                    && ret[to].map(|(x, _)| x) > ret[from].map(|(x, _)| x + weight)
+                    && !neg[to]
                 {
-                    ret[to] = Some((ret[from].unwrap().0 + weight, from));
                     neg[to] = true;
+                    stack.push(to);
                 }
-                if neg[from] {
+            }
+        }
+        while let Some(from) = stack.pop() {
+            for (to, _) in graph.neighbors(from) {
+                if !neg[to] {
                     neg[to] = true;
-                }
-                if neg[to] {
-                    return NegInf
+                    stack.push(to);
                 }
             }
         }
     }
-    if let Some((val,from)) = ret[goal] {
-        let mut from = from;
-        let mut to = goal;
-        let mut pass = Vec::new();
-        while to != start {
-            pass.push((from,to));
-            to = from;
-            from = ret[from].unwrap().1;
-        }
-        return Shortest(val,pass)
-    }
-    unreachable!()
+    (0..size)
+        .map(|v| {
+            if neg[v] {
+                Dist::NegInf
+            } else {
+                match ret[v] {
+                    Some((x, from)) => Dist::Finite(x, from),
+                    None => Dist::Unreachable,
+                }
+            }
+        })
+        .collect()
 }
-*/
 
 #[derive(Debug,Clone,PartialEq,Eq)]
 pub enum Either<U> {
-    Shortest(Vec<Option<(U, usize)>>),
+    Shortest(ShortestPathTree<U>),
     NegCircuit(Vec<(usize, usize)>),
 }
 
+// Potentials p with w + p[u] - p[v] >= 0 on every edge, for dijkstra::solve_with_potential.
+// Every vertex gets one, None if the graph has a negative circuit.
+pub fn feasible_potential<U, A>(
+    graph: &A,
+) -> Option<Vec<U>>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Zero + PartialOrd + Copy,
+{
+    let size = graph.size();
+    let mut extended: ListGraph<U> = Graph::new(size + 1);
+    for from in 0..size {
+        for (to, weight) in graph.neighbors(from) {
+            extended.add_edge(from, to, weight);
+        }
+        extended.add_edge(size, from, U::zero());
+    }
+    match solve(&extended, size) {
+        Either::Shortest(tree) => Some((0..size).map(|v| tree.dist(v).unwrap()).collect()),
+        Either::NegCircuit(_) => None,
+    }
+}
+
 pub fn solve<U: Add + Zero + PartialOrd + Copy, A: Graph<Value = U>>(
     graph: &A, 
     start: usize
 ) -> Either<U> {
+    solve_semiring::<MinPlus<U>, A>(graph, start)
+}
+
+// Same as solve with the relaxation of S, a circuit that keeps improving is reported as NegCircuit.
+pub fn solve_semiring<S: Semiring, A: Graph<Value = S::Value>>(
+    graph: &A,
+    start: usize
+) -> Either<S::Value> {
+    search::<S, A, ()>(graph, start, &mut ())
+}
+
+// Same as solve, reporting every examined edge to visitor. Once no edge relaxes any more,
+// settled is reported for every reachable vertex in increasing order. On Break the
+// distances found so far are returned as Shortest, without looking for negative circuits.
+pub fn solve_with_visitor<U, A, V>(
+    graph: &A,
+    start: usize,
+    visitor: &mut V,
+) -> Either<U>
+where U: Add + Zero + PartialOrd + Copy,
+      A: Graph<Value = U>,
+      V: Visitor<U>,
+{
+    search::<MinPlus<U>, A, V>(graph, start, visitor)
+}
+
+fn search<S: Semiring, A: Graph<Value = S::Value>, V: Visitor<S::Value>>(
+    graph: &A,
+    start: usize,
+    visitor: &mut V,
+) -> Either<S::Value> {
     use Either::*;
     let size = graph.size();
-    let mut ret = vec![None; size];
-    ret[start] = Some((U::zero(), 0));
+    let mut ret: Vec<Option<(S::Value, Option<usize>)>> = vec![None; size];
+    ret[start] = Some((S::identity(), None));
     for _ in 0..size {
         let mut flag = false;
         for from in 0..size {
             for (to,weight) in graph.neighbors(from) {
-                if !ret[from].is_none()
-                    && (ret[to].is_none()
-                        || ret[to].map(|(x, _)| x) > ret[from].map(|(x, _)| x + weight))
-                {
-                    ret[to] = Some((ret[from].unwrap().0 + weight, from));
-                    flag = true;
+                if let Some((d, _)) = ret[from] {
+                    let next = S::extend(d, weight);
+                    let control = if ret[to].is_none() || S::better(next, ret[to].unwrap().0) {
+                        let control = visitor.relaxed(from, to, next);
+                        if control != Control::Prune {
+                            ret[to] = Some((next, Some(from)));
+                            flag = true;
+                        }
+                        control
+                    } else {
+                        visitor.not_relaxed(from, to, next)
+                    };
+                    if control == Control::Break {
+                        return Shortest(ShortestPathTree::new(ret));
+                    }
                 }
             }
         }
         if !flag {
-            return Shortest(ret);
+            for (v, x) in ret.iter().enumerate() {
+                if let Some((d, _)) = *x {
+                    if visitor.settled(v, d) == Control::Break {
+                        break;
+                    }
+                }
+            }
+            return Shortest(ShortestPathTree::new(ret));
         }
     }
     let mut neg = Vec::new();
     for from in 0..size {
         for (to,weight) in graph.neighbors(from) {
-            if !ret[from].is_none()
-                && !ret[to].is_none()
-                && ret[to].map(|(x, _)| x) > ret[from].map(|(x, _)| x + weight)
+            if ret[from].is_some()
+                && ret[to].is_some()
+                && S::better(S::extend(ret[from].unwrap().0, weight), ret[to].unwrap().0)
             {
                 let mut from = from;
                 let mut to = to;
                 for _ in 0..size * size {
                     neg.push((from, to));
                     to = from;
-                    from = ret[from].unwrap().1;
+                    from = match ret[from].unwrap().1 {
+                        Some(x) => x,
+                        None => break,
+                    };
                     if neg[0] == (from, to) {
                         neg.reverse();
                         return NegCircuit(neg);
                     }
                 }
+                neg.clear();
             }
         }
     }
     unreachable!()
 }
+
+// Result of solve_hop_limited / solve_exact_hops, one row of distances per round.
+// A parent of None means the value was carried over from the previous round (or is the start).
+type Round<U> = Vec<Option<(U, Option<usize>)>>;
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct HopLimited<U>(Vec<Round<U>>);
+
+impl<U: Copy> HopLimited<U> {
+    pub fn dist(&self, v: usize) -> Option<U> {
+        self.dist_at(self.0.len() - 1, v)
+    }
+    // distance after round i, i.e. with at most (exactly) i edges
+    pub fn dist_at(&self, i: usize, v: usize) -> Option<U> {
+        self.0[i][v].map(|x| x.0)
+    }
+    // edges from the start to v, in the same order as ShortestPathTree::edges_to
+    pub fn edges_to(&self, v: usize) -> Option<Vec<(usize, usize)>> {
+        let mut i = self.0.len() - 1;
+        self.0[i][v]?;
+        let mut ret = Vec::new();
+        let mut to = v;
+        loop {
+            match self.0[i][to].unwrap().1 {
+                Some(from) => {
+                    ret.push((from, to));
+                    to = from;
+                }
+                None if i == 0 => break,
+                None => {}
+            }
+            i -= 1;
+        }
+        ret.reverse();
+        Some(ret)
+    }
+}
+
+// Shortest distances using at most k edges. Negative circuits are fine, they can only be
+// traversed a bounded number of times.
+pub fn solve_hop_limited<U: Add + Zero + PartialOrd + Copy, A: Graph<Value = U>>(
+    graph: &A,
+    start: usize,
+    k: usize
+) -> HopLimited<U> {
+    rounds(graph, start, k, true)
+}
+
+// Shortest walks using exactly k edges.
+pub fn solve_exact_hops<U: Add + Zero + PartialOrd + Copy, A: Graph<Value = U>>(
+    graph: &A,
+    start: usize,
+    k: usize
+) -> HopLimited<U> {
+    rounds(graph, start, k, false)
+}
+
+fn rounds<U: Add + Zero + PartialOrd + Copy, A: Graph<Value = U>>(
+    graph: &A,
+    start: usize,
+    k: usize,
+    carry: bool
+) -> HopLimited<U> {
+    let size = graph.size();
+    let mut ret = vec![vec![None; size]];
+    ret[0][start] = Some((U::zero(), None));
+    for i in 1..=k {
+        let mut now: Round<U> = if carry {
+            ret[i - 1].iter().map(|x| x.map(|(d, _)| (d, None))).collect()
+        } else {
+            vec![None; size]
+        };
+        for (from, prev) in ret[i - 1].iter().enumerate() {
+            if let Some((d, _)) = *prev {
+                for (to, weight) in graph.neighbors(from) {
+                    if now[to].is_none() || now[to].map(|x| x.0) > Some(d + weight) {
+                        now[to] = Some((d + weight, Some(from)));
+                    }
+                }
+            }
+        }
+        ret.push(now);
+    }
+    HopLimited(ret)
+}
@@ -0,0 +1,33 @@
+use std::collections::VecDeque;
+use crate::graph::Graph;
+use crate::shortest_path::{path_count, ShortestPathTree, TieBreak};
+
+// Every edge counts as length 1, weights are ignored.
+pub fn solve<A: Graph>(
+    graph: &A,
+    start: usize
+) -> ShortestPathTree<usize> {
+    let size = graph.size();
+    let mut ret = vec![None; size];
+    ret[start] = Some((0, None));
+    let mut que = VecDeque::new();
+    que.push_back(start);
+    while let Some(from) = que.pop_front() {
+        let d = ret[from].unwrap().0;
+        for (to, _) in graph.neighbors(from) {
+            if ret[to].is_none() {
+                ret[to] = Some((d + 1, Some(from)));
+                que.push_back(to);
+            }
+        }
+    }
+    ShortestPathTree::new(ret)
+}
+
+pub fn solve_with_tie_break<A: Graph>(
+    graph: &A,
+    start: usize,
+    tie: TieBreak
+) -> ShortestPathTree<usize> {
+    path_count::tie_break(graph, &solve(graph, start), |_| 1, tie)
+}
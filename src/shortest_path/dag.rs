@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+use std::ops::Add;
+use num::Zero;
+use crate::graph::Graph;
+use crate::shortest_path::ShortestPathTree;
+use crate::shortest_path::semiring::{MaxPlus, MinPlus, Semiring};
+
+// Kahn's algorithm. Err holds the edges of a cycle, in order, when the graph is not acyclic.
+pub fn topological_sort<A: Graph>(
+    graph: &A,
+) -> Result<Vec<usize>, Vec<(usize, usize)>> {
+    let size = graph.size();
+    let mut indeg = vec![0; size];
+    for from in 0..size {
+        for (to, _) in graph.neighbors(from) {
+            indeg[to] += 1;
+        }
+    }
+    let mut que: VecDeque<usize> = (0..size).filter(|&v| indeg[v] == 0).collect();
+    let mut order = Vec::with_capacity(size);
+    while let Some(from) = que.pop_front() {
+        order.push(from);
+        for (to, _) in graph.neighbors(from) {
+            indeg[to] -= 1;
+            if indeg[to] == 0 {
+                que.push_back(to);
+            }
+        }
+    }
+    if order.len() == size {
+        return Ok(order);
+    }
+    // every leftover vertex has a leftover predecessor, following them must close a cycle
+    let mut pred = vec![None; size];
+    for from in 0..size {
+        if indeg[from] > 0 {
+            for (to, _) in graph.neighbors(from) {
+                if indeg[to] > 0 {
+                    pred[to] = Some(from);
+                }
+            }
+        }
+    }
+    let mut seen = vec![false; size];
+    let mut v = (0..size).find(|&v| indeg[v] > 0).unwrap();
+    while !seen[v] {
+        seen[v] = true;
+        v = pred[v].unwrap();
+    }
+    let mut cycle = Vec::new();
+    let mut to = v;
+    loop {
+        let from = pred[to].unwrap();
+        cycle.push((from, to));
+        to = from;
+        if to == v {
+            break;
+        }
+    }
+    cycle.reverse();
+    Err(cycle)
+}
+
+// O(V + E) single source shortest paths, negative weights allowed.
+pub fn solve<U: Add + Zero + PartialOrd + Copy, A: Graph<Value = U>>(
+    graph: &A,
+    start: usize,
+) -> Result<ShortestPathTree<U>, Vec<(usize, usize)>> {
+    solve_semiring::<MinPlus<U>, A>(graph, start)
+}
+
+// O(V + E) single source longest paths, e.g. critical paths in project scheduling.
+pub fn solve_longest<U: Add + Zero + PartialOrd + Copy, A: Graph<Value = U>>(
+    graph: &A,
+    start: usize,
+) -> Result<ShortestPathTree<U>, Vec<(usize, usize)>> {
+    solve_semiring::<MaxPlus<U>, A>(graph, start)
+}
+
+pub fn solve_semiring<S: Semiring, A: Graph<Value = S::Value>>(
+    graph: &A,
+    start: usize,
+) -> Result<ShortestPathTree<S::Value>, Vec<(usize, usize)>> {
+    let order = topological_sort(graph)?;
+    let mut ret: Vec<Option<(S::Value, Option<usize>)>> = vec![None; graph.size()];
+    ret[start] = Some((S::identity(), None));
+    for from in order {
+        if let Some((d, _)) = ret[from] {
+            for (to, weight) in graph.neighbors(from) {
+                let next = S::extend(d, weight);
+                if ret[to].is_none() || S::better(next, ret[to].unwrap().0) {
+                    ret[to] = Some((next, Some(from)));
+                }
+            }
+        }
+    }
+    Ok(ShortestPathTree::new(ret))
+}
@@ -1,31 +1,44 @@
 use std::cmp::Ordering;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
-use std::ops::Add;
+use std::marker::PhantomData;
+use std::ops::{Add, Sub};
 use num::Zero;
+use crate::float::Tolerant;
 use crate::graph::Graph;
+use crate::shortest_path::{path_count, Control, ShortestPathTree, TieBreak, Visitor};
+use crate::shortest_path::semiring::{MinPlus, Semiring};
 
 #[derive(Clone, Copy)]
 pub struct Pair<U> {
-    label: usize,
-    value: U,
+    pub(crate) label: usize,
+    pub(crate) value: U,
 }
 
 impl<U> Pair<U> {
-    fn new(l: usize, v: U) -> Pair<U> {
+    pub(crate) fn new(l: usize, v: U) -> Pair<U> {
         Pair { label: l, value: v }
     }
 }
 
 impl<U: PartialOrd + Copy + PartialEq> Ord for Pair<U> {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.value.partial_cmp(&other.value).unwrap()
+        match self.value.partial_cmp(&other.value) {
+            // equal values pop in label order, so the result never depends on heap internals
+            Some(Ordering::Equal) => self.label.cmp(&other.label),
+            Some(ord) => ord,
+            // only NaN is incomparable, order it after everything like f64::total_cmp
+            None => {
+                let nan = |x: &U| x.partial_cmp(x).is_none();
+                nan(&self.value).cmp(&nan(&other.value))
+            }
+        }
     }
 }
 
 impl<U: PartialOrd + Copy> PartialOrd for Pair<U> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.value.partial_cmp(&other.value)
+        Some(self.cmp(other))
     }
 }
 
@@ -40,22 +53,119 @@ impl<U:PartialEq + Copy> Eq for Pair<U> {}
 pub fn solve<U: Add + Zero + PartialOrd + Copy + PartialEq, A: Graph<Value = U>>(
     graph: &A, 
     start: usize
-) -> Vec<Option<(U, usize)>> {
+) -> ShortestPathTree<U> {
+    solve_semiring::<MinPlus<U>, A>(graph, start)
+}
+
+struct Entry<S: Semiring> {
+    label: usize,
+    value: S::Value,
+    semiring: PhantomData<S>,
+}
+
+impl<S: Semiring> Entry<S> {
+    fn new(l: usize, v: S::Value) -> Entry<S> {
+        Entry { label: l, value: v, semiring: PhantomData }
+    }
+}
+
+// the best value is the greatest, so that BinaryHeap pops it first
+impl<S: Semiring> Ord for Entry<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if S::better(self.value, other.value) {
+            Ordering::Greater
+        } else if S::better(other.value, self.value) {
+            Ordering::Less
+        } else {
+            // as for Pair, equal values pop in label order
+            let nan = |x: &S::Value| x.partial_cmp(x).is_none();
+            nan(&other.value).cmp(&nan(&self.value)).then(other.label.cmp(&self.label))
+        }
+    }
+}
+
+impl<S: Semiring> PartialOrd for Entry<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: Semiring> PartialEq for Entry<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S: Semiring> Eq for Entry<S> {}
+
+// Same as solve with the relaxation of S, e.g. solve_semiring::<MaxMin<U>, _> for widest paths.
+pub fn solve_semiring<S: Semiring, A: Graph<Value = S::Value>>(
+    graph: &A,
+    start: usize
+) -> ShortestPathTree<S::Value> {
+    search::<S, A, (), _>(graph, start, |_, _, w| w, &mut ())
+}
+
+// Same as solve, reporting every settled vertex and examined edge to visitor.
+// On Break the distances found so far are returned, only the settled ones are final.
+pub fn solve_with_visitor<U, A, V>(
+    graph: &A,
+    start: usize,
+    visitor: &mut V,
+) -> ShortestPathTree<U>
+where U: Add + Zero + PartialOrd + Copy,
+      A: Graph<Value = U>,
+      V: Visitor<U>,
+{
+    search::<MinPlus<U>, A, V, _>(graph, start, |_, _, w| w, visitor)
+}
+
+// weight(u, v, w) is the length searched for an edge (u, v) of graph with weight w.
+fn search<S, A, V, F>(
+    graph: &A,
+    start: usize,
+    weight: F,
+    visitor: &mut V,
+) -> ShortestPathTree<S::Value>
+where S: Semiring,
+      A: Graph,
+      V: Visitor<S::Value>,
+      F: Fn(usize, usize, A::Value) -> S::Value,
+{
     let size = graph.size();
-    let mut potential = vec![None; size];
-    potential[start] = Some((U::zero(), 0));
+    let mut potential: Vec<Option<(S::Value, Option<usize>)>> = vec![None; size];
+    potential[start] = Some((S::identity(), None));
     let mut heap = BinaryHeap::new();
-    heap.push(Reverse(Pair::new(start, U::zero())));
+    heap.push(Entry::<S>::new(start, S::identity()));
     let mut set = vec![false; size];
     let mut count = 0;
-    while let Some(Reverse(pair)) = heap.pop() {
-        if !set[pair.label] {
-            set[pair.label] = true;
+    'search: while let Some(entry) = heap.pop() {
+        if !set[entry.label] {
+            set[entry.label] = true;
             count += 1;
-            for (v,weight) in graph.neighbors(pair.label) {
-                if !set[v] && (potential[v].is_none() || potential[v].map(|x| x.0) > potential[pair.label].map(|x| x.0+weight)) {
-                    potential[v] = Some((potential[pair.label].unwrap().0 + weight, pair.label));
-                    heap.push(Reverse(Pair::new(v, potential[v].unwrap().0)));
+            let d = potential[entry.label].unwrap().0;
+            match visitor.settled(entry.label, d) {
+                Control::Continue => {}
+                Control::Prune => continue,
+                Control::Break => break,
+            }
+            for (v,w) in graph.neighbors(entry.label) {
+                if set[v] {
+                    continue;
+                }
+                let next = S::extend(d, weight(entry.label, v, w));
+                let control = if potential[v].is_none() || S::better(next, potential[v].unwrap().0) {
+                    let control = visitor.relaxed(entry.label, v, next);
+                    if control != Control::Prune {
+                        potential[v] = Some((next, Some(entry.label)));
+                        heap.push(Entry::new(v, next));
+                    }
+                    control
+                } else {
+                    visitor.not_relaxed(entry.label, v, next)
+                };
+                if control == Control::Break {
+                    break 'search;
                 }
             }
         }
@@ -63,10 +173,29 @@ pub fn solve<U: Add + Zero + PartialOrd + Copy + PartialEq, A: Graph<Value = U>>
             break;
         }
     }
-    potential
+    ShortestPathTree::new(potential)
 }
 
 
+// Dijkstra on the reduced costs w + potential[u] - potential[v], which must be non-negative,
+// without building the reweighted graph. Negative ones within the tolerance of U are rounding
+// errors and count as zero. The tree holds reduced distances, the distance of v
+// in graph itself is dist(v) - potential[start] + potential[v].
+pub fn solve_with_potential<U, A>(
+    graph: &A,
+    start: usize,
+    potential: &[U],
+) -> ShortestPathTree<U>
+where U: Add<Output = U> + Sub<Output = U> + Zero + Tolerant,
+      A: Graph<Value = U>,
+{
+    let reduced = |u: usize, v: usize, w: U| {
+        let c = w + potential[u] - potential[v];
+        if c < U::zero() && !c.improves(U::zero()) { U::zero() } else { c }
+    };
+    search::<MinPlus<U>, A, (), _>(graph, start, reduced, &mut ())
+}
+
 pub fn solve_with_goal<U: Add + Zero + PartialOrd + Copy + PartialEq, A: Graph<Value = U>> (
     graph: &A, 
     start: usize, 
@@ -74,7 +203,7 @@ pub fn solve_with_goal<U: Add + Zero + PartialOrd + Copy + PartialEq, A: Graph<V
 ) -> Option<(U,Vec<(usize,usize)>)> {
     let size = graph.size();
     let mut potential = vec![None; size];
-    potential[start] = Some((U::zero(), 0));
+    potential[start] = Some((U::zero(), None));
     let mut heap = BinaryHeap::new();
     heap.push(Reverse(Pair::new(start, U::zero())));
     let mut set = vec![false; size];
@@ -83,23 +212,161 @@ pub fn solve_with_goal<U: Add + Zero + PartialOrd + Copy + PartialEq, A: Graph<V
             set[pair.label] = true;
             for (v, weight) in graph.neighbors(pair.label) {
                 if !set[v] && (potential[v].is_none() || potential[v].map(|x| x.0) > potential[pair.label].map(|x| x.0+weight)) {
-                    potential[v] = Some((potential[pair.label].unwrap().0 + weight, pair.label));
+                    potential[v] = Some((potential[pair.label].unwrap().0 + weight, Some(pair.label)));
                     heap.push(Reverse(Pair::new(v, potential[v].unwrap().0)));
                 }
             }
         }
         if set[goal] {
-            let (val,from) = potential[goal].unwrap();
-            let mut from = from;
-            let mut to = goal;
-            let mut pass = Vec::new();
-            while to != start {
-                pass.push((from,to));
-                to = from;
-                from = potential[from].unwrap().1;
-            }
-            return Some((val,pass))
+            let tree = ShortestPathTree::new(potential);
+            let mut pass = tree.edges_to(goal).unwrap();
+            pass.reverse();
+            return Some((tree.dist(goal).unwrap(),pass))
         }
     }
     None
 }
+
+pub fn solve_with_tie_break<U: Add + Zero + PartialOrd + Copy + PartialEq, A: Graph<Value = U>>(
+    graph: &A,
+    start: usize,
+    tie: TieBreak
+) -> ShortestPathTree<U> {
+    path_count::tie_break(graph, &solve(graph, start), |w| w, tie)
+}
+
+pub fn solve_with_goal_tie_break<U: Add + Zero + PartialOrd + Copy + PartialEq, A: Graph<Value = U>>(
+    graph: &A,
+    start: usize,
+    goal: usize,
+    tie: TieBreak
+) -> Option<(U,Vec<(usize,usize)>)> {
+    let size = graph.size();
+    let mut up_to = UpTo { goal, dist: None, settled: vec![false; size] };
+    let tree = solve_with_visitor(graph, start, &mut up_to);
+    up_to.dist?;
+    let tree = ShortestPathTree::new((0..size).map(|v| {
+        if up_to.settled[v] { tree.dist(v).map(|d| (d, tree.parent(v))) } else { None }
+    }).collect());
+    let tree = path_count::tie_break(graph, &tree, |w| w, tie);
+    let mut pass = tree.edges_to(goal)?;
+    pass.reverse();
+    Some((tree.dist(goal).unwrap(),pass))
+}
+
+// Settles every vertex no farther than goal, which covers all shortest paths to it, then stops.
+struct UpTo<U> {
+    goal: usize,
+    dist: Option<U>,
+    settled: Vec<bool>,
+}
+
+impl<U: PartialOrd + Copy> Visitor<U> for UpTo<U> {
+    fn settled(&mut self, v: usize, d: U) -> Control {
+        match self.dist {
+            Some(g) if d > g => return Control::Break,
+            _ => {}
+        }
+        self.settled[v] = true;
+        if v == self.goal {
+            self.dist = Some(d);
+        }
+        Control::Continue
+    }
+}
+
+// reverse_graph must hold the edge (v, u, w) for every edge (u, v, w) of graph.
+pub fn solve_bidirectional<U: Add<Output = U> + Zero + PartialOrd + Copy + PartialEq, A: Graph<Value = U>> (
+    graph: &A,
+    reverse_graph: &A,
+    start: usize,
+    goal: usize
+) -> Option<(U,Vec<(usize,usize)>)> {
+    let size = graph.size();
+    let mut potential = [vec![None; size], vec![None; size]];
+    potential[0][start] = Some((U::zero(), None));
+    potential[1][goal] = Some((U::zero(), None));
+    let mut heap = [BinaryHeap::new(), BinaryHeap::new()];
+    heap[0].push(Reverse(Pair::new(start, U::zero())));
+    heap[1].push(Reverse(Pair::new(goal, U::zero())));
+    let mut set = [vec![false; size], vec![false; size]];
+    let mut best: Option<(U, usize)> = if start == goal { Some((U::zero(), start)) } else { None };
+    while let (Some(Reverse(x)), Some(Reverse(y))) = (heap[0].peek(), heap[1].peek()) {
+        if let Some((mu, _)) = best {
+            if x.value + y.value >= mu {
+                break;
+            }
+        }
+        let side = if x.value <= y.value { 0 } else { 1 };
+        let Reverse(pair) = heap[side].pop().unwrap();
+        if set[side][pair.label] {
+            continue;
+        }
+        set[side][pair.label] = true;
+        let d = potential[side][pair.label].unwrap().0;
+        let g = if side == 0 { graph } else { reverse_graph };
+        for (v, weight) in g.neighbors(pair.label) {
+            if !set[side][v] && (potential[side][v].is_none() || potential[side][v].map(|x| x.0) > Some(d + weight)) {
+                potential[side][v] = Some((d + weight, Some(pair.label)));
+                heap[side].push(Reverse(Pair::new(v, d + weight)));
+                if let Some((other, _)) = potential[1 - side][v] {
+                    if best.is_none() || best.map(|x| x.0) > Some(d + weight + other) {
+                        best = Some((d + weight + other, v));
+                    }
+                }
+            }
+        }
+    }
+    let (val, meet) = best?;
+    let [forward, backward] = potential;
+    let mut pass: Vec<(usize, usize)> = ShortestPathTree::new(backward)
+        .edges_to(meet)
+        .unwrap()
+        .into_iter()
+        .map(|(from, to)| (to, from))
+        .collect();
+    pass.extend(ShortestPathTree::new(forward).edges_to(meet).unwrap().into_iter().rev());
+    Some((val, pass))
+}
+
+// The second vector holds the source each vertex is nearest to.
+pub fn solve_multi<U: Add + Zero + PartialOrd + Copy + PartialEq, A: Graph<Value = U>>(
+    graph: &A,
+    sources: &[usize]
+) -> (ShortestPathTree<U>, Vec<Option<usize>>) {
+    let sources: Vec<(usize, U)> = sources.iter().map(|&s| (s, U::zero())).collect();
+    solve_multi_with_offset(graph, &sources)
+}
+
+// Each source s starts at distance offset instead of zero.
+pub fn solve_multi_with_offset<U: Add + Zero + PartialOrd + Copy + PartialEq, A: Graph<Value = U>>(
+    graph: &A,
+    sources: &[(usize, U)]
+) -> (ShortestPathTree<U>, Vec<Option<usize>>) {
+    let size = graph.size();
+    let mut potential: Vec<Option<(U, Option<usize>)>> = vec![None; size];
+    let mut nearest = vec![None; size];
+    let mut heap = BinaryHeap::new();
+    for &(s, offset) in sources {
+        if potential[s].is_none() || potential[s].map(|x| x.0) > Some(offset) {
+            potential[s] = Some((offset, None));
+            nearest[s] = Some(s);
+            heap.push(Reverse(Pair::new(s, offset)));
+        }
+    }
+    let mut set = vec![false; size];
+    while let Some(Reverse(pair)) = heap.pop() {
+        if !set[pair.label] {
+            set[pair.label] = true;
+            let d = potential[pair.label].unwrap().0;
+            for (v, weight) in graph.neighbors(pair.label) {
+                if !set[v] && (potential[v].is_none() || potential[v].map(|x| x.0) > Some(d + weight)) {
+                    potential[v] = Some((d + weight, Some(pair.label)));
+                    nearest[v] = nearest[pair.label];
+                    heap.push(Reverse(Pair::new(v, d + weight)));
+                }
+            }
+        }
+    }
+    (ShortestPathTree::new(potential), nearest)
+}
@@ -0,0 +1,128 @@
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+use std::ops::Add;
+use num::Zero;
+use crate::graph::{AccGraph, Graph, MatGraph};
+use crate::shortest_path::dijkstra::{self, Pair};
+use crate::shortest_path::ShortestPathTree;
+
+// Single source shortest paths kept up to date under edge insertions, weights must be non-negative.
+pub struct DynamicSssp<A: Graph> {
+    graph: A,
+    tree: ShortestPathTree<A::Value>,
+}
+
+impl<A, U> DynamicSssp<A>
+    where A: Graph<Value = U>,
+          U: Add<Output = U> + Zero + PartialOrd + Copy,
+{
+    pub fn new(graph: A, start: usize) -> Self {
+        let tree = dijkstra::solve(&graph, start);
+        DynamicSssp {
+            graph,
+            tree,
+        }
+    }
+    pub fn graph(&self) -> &A {
+        &self.graph
+    }
+    pub fn tree(&self) -> &ShortestPathTree<U> {
+        &self.tree
+    }
+    pub fn dist(&self, v: usize) -> Option<U> {
+        self.tree.dist(v)
+    }
+    // Also serves as a weight decrease: ListGraph keeps both edges, MatGraph and MapGraph
+    // overwrite the old weight. Increases are not supported, so on the latter w must not
+    // exceed the old weight, whereas a heavier parallel edge in a ListGraph changes nothing.
+    pub fn add_edge(&mut self, u: usize, v: usize, w: U) {
+        let parallel = self.graph.neighbors(u).filter(|&(to, _)| to == v).count();
+        let increased = self.graph.neighbors(u).any(|(to, old)| to == v && w > old);
+        self.graph.add_edge(u, v, w);
+        let overwritten = self.graph.neighbors(u).filter(|&(to, _)| to == v).count() == parallel;
+        assert!(!(overwritten && increased), "weight of ({}, {}) increased", u, v);
+        let d = match self.tree.dist(u) {
+            Some(d) => d,
+            None => return,
+        };
+        if self.tree.dist(v).is_some() && self.tree.dist(v) <= Some(d + w) {
+            return;
+        }
+        self.tree.0[v] = Some((d + w, Some(u)));
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse(Pair::new(v, d + w)));
+        while let Some(Reverse(pair)) = heap.pop() {
+            if self.tree.dist(pair.label) < Some(pair.value) {
+                continue;
+            }
+            for (to, weight) in self.graph.neighbors(pair.label) {
+                let next = pair.value + weight;
+                if self.tree.dist(to).is_none() || self.tree.dist(to) > Some(next) {
+                    self.tree.0[to] = Some((next, Some(pair.label)));
+                    heap.push(Reverse(Pair::new(to, next)));
+                }
+            }
+        }
+    }
+}
+
+// All pairs shortest distances of a MatGraph (Floyd-Warshall) with O(n^2) edge insertions.
+pub struct DynamicApsp<U> {
+    graph: MatGraph<U>,
+    dist: Vec<Vec<Option<U>>>,
+}
+
+impl<U: Add<Output = U> + Zero + PartialOrd + Copy> DynamicApsp<U> {
+    pub fn new(graph: MatGraph<U>) -> Self {
+        let size = graph.size();
+        let mut ret = DynamicApsp {
+            graph,
+            dist: vec![vec![None; size]; size],
+        };
+        for from in 0..size {
+            ret.dist[from][from] = Some(U::zero());
+        }
+        for from in 0..size {
+            for to in 0..size {
+                if let Some(weight) = ret.graph.get(from, to) {
+                    ret.relax(from, to, Some(U::zero()), Some(weight));
+                }
+            }
+        }
+        for k in 0..size {
+            for i in 0..size {
+                for j in 0..size {
+                    ret.relax(i, j, ret.dist[i][k], ret.dist[k][j]);
+                }
+            }
+        }
+        ret
+    }
+    pub fn graph(&self) -> &MatGraph<U> {
+        &self.graph
+    }
+    pub fn dist(&self, u: usize, v: usize) -> Option<U> {
+        self.dist[u][v]
+    }
+    // Adds the edge or decreases its weight, panics if it would increase it.
+    pub fn add_edge(&mut self, u: usize, v: usize, w: U) {
+        if let Some(old) = self.graph.get(u, v) {
+            assert!(w <= old, "weight of ({}, {}) increased", u, v);
+        }
+        self.graph.add_edge(u, v, w);
+        let size = self.dist.len();
+        for i in 0..size {
+            let through = self.dist[i][u].map(|x| x + w);
+            for j in 0..size {
+                self.relax(i, j, through, self.dist[v][j]);
+            }
+        }
+    }
+    fn relax(&mut self, i: usize, j: usize, a: Option<U>, b: Option<U>) {
+        if let (Some(a), Some(b)) = (a, b) {
+            if self.dist[i][j].is_none() || self.dist[i][j] > Some(a + b) {
+                self.dist[i][j] = Some(a + b);
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::ops::{Add, Sub};
+use num::Zero;
+use crate::float::Tolerant;
+use crate::graph::{Graph, ListGraph};
+use crate::shortest_path::{bellman_ford, dijkstra, ShortestPathTree};
+
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum Either<U> {
+    Shortest(Vec<ShortestPathTree<U>>),
+    NegCircuit(Vec<(usize, usize)>),
+}
+
+// ret[s] is the tree dijkstra::solve(graph, s) would give if every weight were non-negative.
+pub fn solve<U, A>(
+    graph: &A,
+) -> Either<U>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Sub<Output = U> + Zero + Tolerant,
+{
+    let size = graph.size();
+    let mut extended: ListGraph<U> = Graph::new(size + 1);
+    for from in 0..size {
+        for (to, weight) in graph.neighbors(from) {
+            extended.add_edge(from, to, weight);
+        }
+        extended.add_edge(size, from, U::zero());
+    }
+    let h: Vec<U> = match bellman_ford::solve(&extended, size) {
+        bellman_ford::Either::Shortest(tree) => (0..size).map(|v| tree.dist(v).unwrap()).collect(),
+        bellman_ford::Either::NegCircuit(circuit) => return Either::NegCircuit(circuit),
+    };
+    let ret = (0..size)
+        .map(|s| {
+            let tree = dijkstra::solve_with_potential(graph, s, &h);
+            ShortestPathTree::new((0..size).map(|v| tree.dist(v).map(|d| (d - h[s] + h[v], tree.parent(v)))).collect())
+        })
+        .collect();
+    Either::Shortest(ret)
+}
@@ -0,0 +1,240 @@
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+use std::ops::{Add, Sub};
+use num::Zero;
+use crate::graph::{Graph, ListGraph};
+use crate::shortest_path::dijkstra::{self, Pair};
+use crate::shortest_path::ShortestPathTree;
+
+// K shortest loopless paths in increasing order of cost, each in the format of dijkstra::solve_with_goal.
+pub fn yen<U, A>(
+    graph: &A,
+    start: usize,
+    goal: usize,
+    k: usize,
+) -> Vec<(U,Vec<(usize,usize)>)>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Zero + PartialOrd + Copy,
+{
+    let size = graph.size();
+    let mut ret: Vec<(U, Vec<usize>)> = Vec::new();
+    let mut candidates: Vec<(U, Vec<usize>)> = Vec::new();
+    if k == 0 {
+        return Vec::new();
+    }
+    match dijkstra::solve_with_goal(graph, start, goal) {
+        None => return Vec::new(),
+        Some((val, pass)) => ret.push((val, to_vertices(start, &pass))),
+    }
+    while ret.len() < k {
+        let prev = ret.last().unwrap().1.clone();
+        let mut root_cost = U::zero();
+        for j in 0..prev.len() - 1 {
+            let spur = prev[j];
+            let root = &prev[..=j];
+            let mut removed = vec![false; size];
+            for &v in &prev[..j] {
+                removed[v] = true;
+            }
+            let mut spur_graph: ListGraph<U> = Graph::new(size);
+            for from in 0..size {
+                if removed[from] {
+                    continue;
+                }
+                for (to, weight) in graph.neighbors(from) {
+                    let used = from == spur && ret.iter().any(|(_, p)| p.len() > j + 1 && &p[..=j] == root && p[j + 1] == to);
+                    if !removed[to] && !used {
+                        spur_graph.add_edge(from, to, weight);
+                    }
+                }
+            }
+            if let Some((val, pass)) = dijkstra::solve_with_goal(&spur_graph, spur, goal) {
+                let mut path = root.to_vec();
+                path.extend_from_slice(&to_vertices(spur, &pass)[1..]);
+                if !candidates.iter().any(|(_, p)| *p == path) {
+                    candidates.push((root_cost + val, path));
+                }
+            }
+            root_cost = root_cost + weight_of(graph, prev[j], prev[j + 1]);
+        }
+        if candidates.is_empty() {
+            break;
+        }
+        let mut best = 0;
+        for i in 1..candidates.len() {
+            if candidates[i].0 < candidates[best].0 {
+                best = i;
+            }
+        }
+        ret.push(candidates.swap_remove(best));
+    }
+    ret.into_iter()
+        .map(|(val, path)| (val, path.windows(2).rev().map(|x| (x[0], x[1])).collect()))
+        .collect()
+}
+
+fn to_vertices(start: usize, pass: &[(usize, usize)]) -> Vec<usize> {
+    let mut ret = vec![start];
+    ret.extend(pass.iter().rev().map(|&(_, to)| to));
+    ret
+}
+
+fn weight_of<U: PartialOrd + Copy, A: Graph<Value = U>>(graph: &A, from: usize, to: usize) -> U {
+    let mut ret = None;
+    for (v, weight) in graph.neighbors(from) {
+        if v == to && (ret.is_none() || Some(weight) < ret) {
+            ret = Some(weight);
+        }
+    }
+    ret.unwrap()
+}
+
+// sidetrack edge (from, to) with its extra cost key over the shortest path
+struct Node<U> {
+    key: U,
+    from: usize,
+    to: usize,
+    rank: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+// persistent leftist heap, merging never touches existing nodes.
+fn merge<U: PartialOrd + Copy>(nodes: &mut Vec<Node<U>>, a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    let (a, b) = match (a, b) {
+        (None, x) | (x, None) => return x,
+        (Some(a), Some(b)) => if nodes[a].key > nodes[b].key { (b, a) } else { (a, b) },
+    };
+    let right = merge(nodes, nodes[a].right, Some(b));
+    let left = nodes[a].left;
+    let rank = |x: Option<usize>, nodes: &Vec<Node<U>>| x.map_or(0, |x| nodes[x].rank);
+    let (left, right) = if rank(left, nodes) < rank(right, nodes) { (right, left) } else { (left, right) };
+    let node = Node { key: nodes[a].key, from: nodes[a].from, to: nodes[a].to, rank: rank(right, nodes) + 1, left, right };
+    nodes.push(node);
+    Some(nodes.len() - 1)
+}
+
+// K shortest start-goal walks in increasing order of cost, vertices and edges may repeat.
+// Each is in the format of dijkstra::solve_with_goal.
+pub fn eppstein<U, A>(
+    graph: &A,
+    start: usize,
+    goal: usize,
+    k: usize,
+) -> Vec<(U,Vec<(usize,usize)>)>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Sub<Output = U> + Zero + PartialOrd + Copy,
+{
+    let size = graph.size();
+    let mut reverse_graph: ListGraph<U> = Graph::new(size);
+    for from in 0..size {
+        for (to, weight) in graph.neighbors(from) {
+            reverse_graph.add_edge(to, from, weight);
+        }
+    }
+    let dist = dijkstra::solve(&reverse_graph, goal);
+    if k == 0 || !dist.is_reachable(start) {
+        return Vec::new();
+    }
+    let mut nodes: Vec<Node<U>> = Vec::new();
+    let mut heap_of: Vec<Option<Option<usize>>> = vec![None; size];
+    let mut ret = vec![(dist.dist(start).unwrap(), walk(&dist, &nodes, &[], start))];
+    // the sidetracks taken so far, as (node, previous) linked lists shared between walks
+    let mut taken: Vec<(usize, Option<usize>)> = Vec::new();
+    // candidate walks: the sidetracks in taken (if any) followed by node
+    let mut candidates: Vec<(usize, Option<usize>)> = Vec::new();
+    let root = build(graph, &dist, start, &mut nodes, &mut heap_of);
+    let mut que = BinaryHeap::new();
+    if let Some(root) = root {
+        candidates.push((root, None));
+        que.push(Reverse(Pair::new(0, dist.dist(start).unwrap() + nodes[root].key)));
+    }
+    while ret.len() < k {
+        let Reverse(pair) = match que.pop() {
+            Some(x) => x,
+            None => break,
+        };
+        let (node, prev) = candidates[pair.label];
+        taken.push((node, prev));
+        let now = taken.len() - 1;
+        let mut sidetracks = Vec::new();
+        let mut at = Some(now);
+        while let Some(x) = at {
+            sidetracks.push(taken[x].0);
+            at = taken[x].1;
+        }
+        sidetracks.reverse();
+        ret.push((pair.value, walk(&dist, &nodes, &sidetracks, start)));
+        let key = nodes[node].key;
+        for &child in [nodes[node].left, nodes[node].right].iter().flatten() {
+            candidates.push((child, prev));
+            que.push(Reverse(Pair::new(candidates.len() - 1, pair.value - key + nodes[child].key)));
+        }
+        if let Some(next) = build(graph, &dist, nodes[node].to, &mut nodes, &mut heap_of) {
+            candidates.push((next, Some(now)));
+            que.push(Reverse(Pair::new(candidates.len() - 1, pair.value + nodes[next].key)));
+        }
+    }
+    ret
+}
+
+// the walk following the shortest path tree towards goal except for the given sidetracks
+fn walk<U: Copy>(dist: &ShortestPathTree<U>, nodes: &[Node<U>], sidetracks: &[usize], start: usize) -> Vec<(usize,usize)> {
+    let mut pass = Vec::new();
+    let mut now = start;
+    for &x in sidetracks {
+        while now != nodes[x].from {
+            let next = dist.parent(now).unwrap();
+            pass.push((now, next));
+            now = next;
+        }
+        pass.push((now, nodes[x].to));
+        now = nodes[x].to;
+    }
+    while let Some(next) = dist.parent(now) {
+        pass.push((now, next));
+        now = next;
+    }
+    pass.reverse();
+    pass
+}
+
+// heap of every sidetrack edge leaving the shortest path tree somewhere on the way from v to goal.
+fn build<U, A>(
+    graph: &A,
+    dist: &ShortestPathTree<U>,
+    v: usize,
+    nodes: &mut Vec<Node<U>>,
+    heap_of: &mut [Option<Option<usize>>],
+) -> Option<usize>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Sub<Output = U> + Zero + PartialOrd + Copy,
+{
+    let mut chain = Vec::new();
+    let mut now = v;
+    while heap_of[now].is_none() {
+        chain.push(now);
+        match dist.parent(now) {
+            Some(next) => now = next,
+            None => break,
+        }
+    }
+    while let Some(from) = chain.pop() {
+        let next = dist.parent(from);
+        let mut skipped = next.is_none();
+        let mut heap = next.and_then(|x| heap_of[x].unwrap());
+        for (to, weight) in graph.neighbors(from) {
+            if let (Some(d_from), Some(d_to)) = (dist.dist(from), dist.dist(to)) {
+                if !skipped && Some(to) == next && weight + d_to == d_from {
+                    skipped = true;
+                    continue;
+                }
+                nodes.push(Node { key: weight + d_to - d_from, from, to, rank: 1, left: None, right: None });
+                let single = Some(nodes.len() - 1);
+                heap = merge(nodes, heap, single);
+            }
+        }
+        heap_of[from] = Some(heap);
+    }
+    heap_of[v].unwrap()
+}
@@ -0,0 +1,66 @@
+use crate::graph::Graph;
+use crate::shortest_path::ShortestPathTree;
+
+// Product of a graph with layers 0..=k, vertex v of layer l is l * size + v.
+// Typical use is "up to k edges may be taken for free": lift, run dijkstra::solve from
+// index(s, 0), then take best(&tree, t).
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Layered {
+    size: usize,
+    k: usize,
+}
+
+impl Layered {
+    pub fn new(size: usize, k: usize) -> Self {
+        Layered {
+            size,
+            k,
+        }
+    }
+    pub fn size(&self) -> usize {
+        self.size * (self.k + 1)
+    }
+    pub fn index(&self, v: usize, layer: usize) -> usize {
+        layer * self.size + v
+    }
+    pub fn split(&self, x: usize) -> (usize, usize) {
+        (x % self.size, x / self.size)
+    }
+    // Every edge (u, v, w) stays inside each layer, and also leads from layer l to l + 1
+    // with weight w' when modify(u, v, w) returns Some(w').
+    pub fn lift<A, B, F>(&self, graph: &A, modify: F) -> B
+    where A: Graph,
+          B: Graph<Value = A::Value>,
+          F: Fn(usize, usize, A::Value) -> Option<A::Value>,
+    {
+        let mut ret = B::new(self.size());
+        for from in 0..self.size {
+            for (to, weight) in graph.neighbors(from) {
+                let modified = modify(from, to, weight);
+                for layer in 0..=self.k {
+                    ret.add_edge(self.index(from, layer), self.index(to, layer), weight);
+                    if let (Some(w), true) = (modified, layer < self.k) {
+                        ret.add_edge(self.index(from, layer), self.index(to, layer + 1), w);
+                    }
+                }
+            }
+        }
+        ret
+    }
+    // (distance, layer) of the closest copy of v
+    pub fn best<U: PartialOrd + Copy>(&self, tree: &ShortestPathTree<U>, v: usize) -> Option<(U, usize)> {
+        let mut ret: Option<(U, usize)> = None;
+        for layer in 0..=self.k {
+            if let Some(d) = tree.dist(self.index(v, layer)) {
+                if ret.is_none() || d < ret.unwrap().0 {
+                    ret = Some((d, layer));
+                }
+            }
+        }
+        ret
+    }
+    // (vertex, layer) pairs from the root to v in the given layer
+    pub fn path_to<U: Copy>(&self, tree: &ShortestPathTree<U>, v: usize, layer: usize) -> Option<Vec<(usize, usize)>> {
+        tree.path_to(self.index(v, layer)).map(|path| path.into_iter().map(|x| self.split(x)).collect())
+    }
+}
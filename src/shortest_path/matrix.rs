@@ -0,0 +1,105 @@
+use std::ops::{Add, Mul};
+use num::{One, Zero};
+use crate::graph::{AccGraph, Graph};
+use crate::shortest_path::semiring::Semiring;
+
+// adjacency matrix, None where there is no edge (no walk)
+pub type Matrix<U> = Vec<Vec<Option<U>>>;
+
+pub fn from_graph<A: AccGraph>(graph: &A) -> Matrix<A::Value> {
+    let size = graph.size();
+    (0..size).map(|u| (0..size).map(|v| graph.get(u, v)).collect()).collect()
+}
+
+// matrix of the empty walks
+pub fn identity<S: Semiring>(size: usize) -> Matrix<S::Value> {
+    (0..size).map(|u| (0..size).map(|v| if u == v { Some(S::identity()) } else { None }).collect()).collect()
+}
+
+// best walks made of a walk in a followed by a walk in b
+pub fn multiply<S: Semiring>(a: &Matrix<S::Value>, b: &Matrix<S::Value>) -> Matrix<S::Value> {
+    let size = a.len();
+    let mut ret = vec![vec![None; size]; size];
+    for (row, a_row) in ret.iter_mut().zip(a.iter()) {
+        for (x, b_row) in a_row.iter().zip(b.iter()) {
+            let x = match *x {
+                Some(x) => x,
+                None => continue,
+            };
+            for (cell, y) in row.iter_mut().zip(b_row.iter()) {
+                if let Some(y) = *y {
+                    let d = S::extend(x, y);
+                    if cell.is_none() || S::better(d, cell.unwrap()) {
+                        *cell = Some(d);
+                    }
+                }
+            }
+        }
+    }
+    ret
+}
+
+// best walks of exactly k edges, e.g. power::<MinPlus<_>>(&from_graph(&graph), k) for shortest ones
+pub fn power<S: Semiring>(a: &Matrix<S::Value>, mut k: u64) -> Matrix<S::Value> {
+    let mut ret = identity::<S>(a.len());
+    let mut base = a.clone();
+    while k > 0 {
+        if k & 1 == 1 {
+            ret = multiply::<S>(&ret, &base);
+        }
+        k >>= 1;
+        if k > 0 {
+            base = multiply::<S>(&base, &base);
+        }
+    }
+    ret
+}
+
+// number of edges from u to v, parallel edges counted separately
+pub fn counts<C, A>(graph: &A) -> Vec<Vec<C>>
+where A: Graph,
+      C: Zero + One + Copy,
+{
+    let size = graph.size();
+    let mut ret = vec![vec![C::zero(); size]; size];
+    for (u, row) in ret.iter_mut().enumerate() {
+        for (v, _) in graph.neighbors(u) {
+            row[v] = row[v] + C::one();
+        }
+    }
+    ret
+}
+
+pub fn multiply_counts<C>(a: &[Vec<C>], b: &[Vec<C>]) -> Vec<Vec<C>>
+where C: Add<Output = C> + Mul<Output = C> + Zero + Copy,
+{
+    let size = a.len();
+    let mut ret = vec![vec![C::zero(); size]; size];
+    for (row, a_row) in ret.iter_mut().zip(a.iter()) {
+        for (&x, b_row) in a_row.iter().zip(b.iter()) {
+            for (cell, &y) in row.iter_mut().zip(b_row.iter()) {
+                *cell = *cell + x * y;
+            }
+        }
+    }
+    ret
+}
+
+// number of walks of exactly k edges, use a modular C such as ModInt for large k
+pub fn power_counts<C>(a: &[Vec<C>], mut k: u64) -> Vec<Vec<C>>
+where C: Add<Output = C> + Mul<Output = C> + Zero + One + Copy,
+{
+    let size = a.len();
+    let mut ret: Vec<Vec<C>> = (0..size).map(|u| (0..size).map(|v| if u == v { C::one() } else { C::zero() }).collect()).collect();
+    let mut base = a.to_vec();
+    while k > 0 {
+        if k & 1 == 1 {
+            ret = multiply_counts(&ret, &base);
+        }
+        k >>= 1;
+        if k > 0 {
+            base = multiply_counts(&base, &base);
+        }
+    }
+    ret
+}
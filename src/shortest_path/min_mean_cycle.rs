@@ -0,0 +1,67 @@
+use num::{FromPrimitive, Integer};
+use num::rational::Ratio;
+use crate::graph::Graph;
+
+pub type Cycle = Vec<(usize, usize)>;
+
+// Karp's algorithm, O(VE). Returns the minimum mean weight over all cycles together with
+// one cycle attaining it (edges in order), or None if the graph is acyclic.
+pub fn solve<U, A>(
+    graph: &A,
+) -> Option<(Ratio<U>, Cycle)>
+where A: Graph<Value = U>,
+      U: Integer + FromPrimitive + Copy,
+{
+    let size = graph.size();
+    // d[k][v]: minimum weight of a walk with exactly k edges ending at v, starting anywhere
+    let mut d: Vec<Vec<Option<U>>> = vec![vec![None; size]; size + 1];
+    let mut pred = vec![vec![0; size]; size + 1];
+    d[0] = vec![Some(U::zero()); size];
+    for k in 1..=size {
+        for from in 0..size {
+            if let Some(x) = d[k - 1][from] {
+                for (to, weight) in graph.neighbors(from) {
+                    if d[k][to].is_none() || Some(x + weight) < d[k][to] {
+                        d[k][to] = Some(x + weight);
+                        pred[k][to] = from;
+                    }
+                }
+            }
+        }
+    }
+    let mut best: Option<(Ratio<U>, usize)> = None;
+    for (v, &dn) in d[size].iter().enumerate() {
+        if let Some(dn) = dn {
+            let mut worst: Option<Ratio<U>> = None;
+            for (k, row) in d.iter().enumerate().take(size) {
+                if let Some(dk) = row[v] {
+                    let mean = Ratio::new(dn - dk, U::from_usize(size - k).unwrap());
+                    if worst.is_none() || Some(mean) > worst {
+                        worst = Some(mean);
+                    }
+                }
+            }
+            let worst = worst.unwrap();
+            if best.is_none() || worst < best.unwrap().0 {
+                best = Some((worst, v));
+            }
+        }
+    }
+    let (mean, v) = best?;
+    // any cycle on the walk realizing d[size][v] attains the minimum mean
+    let mut walk = vec![v];
+    for k in (1..=size).rev() {
+        let last = *walk.last().unwrap();
+        walk.push(pred[k][last]);
+    }
+    walk.reverse();
+    let mut seen = vec![None; size];
+    for (j, &x) in walk.iter().enumerate() {
+        if let Some(i) = seen[x] {
+            let cycle = walk[i..=j].windows(2).map(|e| (e[0], e[1])).collect();
+            return Some((mean, cycle));
+        }
+        seen[x] = Some(j);
+    }
+    unreachable!()
+}
@@ -0,0 +1,75 @@
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+use std::ops::Add;
+use num::Zero;
+use crate::graph::Graph;
+use crate::shortest_path::dijkstra::Pair;
+
+// Pareto-optimal (cost1, cost2) distances of every vertex, each with one representative path.
+pub struct Frontier<U, V> {
+    // (vertex, costs, previous label) of every label created during the search
+    labels: Vec<(usize, (U, V), Option<usize>)>,
+    // settled labels of each vertex, increasing in cost1 and strictly decreasing in cost2
+    frontier: Vec<Vec<usize>>,
+}
+
+impl<U: Copy, V: Copy> Frontier<U, V> {
+    pub fn size(&self) -> usize {
+        self.frontier.len()
+    }
+
+    pub fn costs(&self, v: usize) -> Vec<(U, V)> {
+        self.frontier[v].iter().map(|&l| self.labels[l].1).collect()
+    }
+
+    // edges of the path realising costs(v)[i], in order from the start
+    pub fn path(&self, v: usize, i: usize) -> Vec<(usize, usize)> {
+        let mut pass = Vec::new();
+        let mut now = self.frontier[v][i];
+        while let Some(prev) = self.labels[now].2 {
+            pass.push((self.labels[prev].0, self.labels[now].0));
+            now = prev;
+        }
+        pass.reverse();
+        pass
+    }
+}
+
+// Martins' label-setting algorithm, both costs must be non-negative.
+// Labels are settled in lexicographic order, so a label is dominated exactly when
+// the last settled label of its vertex has a cost2 no larger than its own.
+pub fn solve<U, V, A>(
+    graph: &A,
+    start: usize,
+) -> Frontier<U, V>
+where A: Graph<Value = (U, V)>,
+      U: Add<Output = U> + Zero + PartialOrd + Copy,
+      V: Add<Output = V> + Zero + PartialOrd + Copy,
+{
+    let mut labels = vec![(start, (U::zero(), V::zero()), None)];
+    let mut frontier: Vec<Vec<usize>> = vec![Vec::new(); graph.size()];
+    let dominated = |frontier: &Vec<Vec<usize>>, labels: &Vec<(usize, (U, V), Option<usize>)>, v: usize, c2: V| {
+        match frontier[v].last() {
+            Some(&l) => labels[l].1.1 <= c2,
+            None => false,
+        }
+    };
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse(Pair::new(0, (U::zero(), V::zero()))));
+    while let Some(Reverse(pair)) = heap.pop() {
+        let (v, (c1, c2), _) = labels[pair.label];
+        if dominated(&frontier, &labels, v, c2) {
+            continue;
+        }
+        frontier[v].push(pair.label);
+        for (to, (w1, w2)) in graph.neighbors(v) {
+            let next = (c1 + w1, c2 + w2);
+            if dominated(&frontier, &labels, to, next.1) {
+                continue;
+            }
+            labels.push((to, next, Some(pair.label)));
+            heap.push(Reverse(Pair::new(labels.len() - 1, next)));
+        }
+    }
+    Frontier { labels, frontier }
+}
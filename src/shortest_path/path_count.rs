@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+use std::ops::Add;
+use num::{One, Zero};
+use crate::graph::{Graph, ListGraph};
+use crate::shortest_path::{ShortestPathTree, TieBreak};
+
+// Keeps exactly the edges lying on some shortest path, dist is the output of dijkstra::solve
+// or bellman_ford::solve. The result is acyclic unless the graph has a zero-weight cycle.
+pub fn mk_dag<U, A>(
+    graph: &A,
+    dist: &ShortestPathTree<U>,
+) -> ListGraph<U>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + PartialEq + Copy,
+{
+    mk_dag_by(graph, dist, |w| w)
+}
+
+// Same as mk_dag, with the length of each edge given by weight.
+// Use mk_dag_by(graph, &bfs::solve(graph, s), |_| 1) for unweighted graphs.
+pub fn mk_dag_by<D, A, F>(
+    graph: &A,
+    dist: &ShortestPathTree<D>,
+    weight: F,
+) -> ListGraph<A::Value>
+where A: Graph,
+      D: Add<Output = D> + PartialEq + Copy,
+      F: Fn(A::Value) -> D,
+{
+    let size = graph.size();
+    let mut dag: ListGraph<A::Value> = Graph::new(size);
+    for from in 0..size {
+        if let Some(d) = dist.dist(from) {
+            for (to, w) in graph.neighbors(from) {
+                if dist.dist(to) == Some(d + weight(w)) {
+                    dag.add_edge(from, to, w);
+                }
+            }
+        }
+    }
+    dag
+}
+
+// Number of start-v paths in dag for every v, counted in C (e.g. a modular integer).
+pub fn count<C, A>(
+    dag: &A,
+    start: usize,
+) -> Vec<C>
+where A: Graph,
+      C: Zero + One + Copy,
+{
+    let size = dag.size();
+    let mut indeg = vec![0; size];
+    for from in 0..size {
+        for (to, _) in dag.neighbors(from) {
+            indeg[to] += 1;
+        }
+    }
+    let mut ret = vec![C::zero(); size];
+    ret[start] = C::one();
+    let mut que: VecDeque<usize> = (0..size).filter(|&v| indeg[v] == 0).collect();
+    while let Some(from) = que.pop_front() {
+        for (to, _) in dag.neighbors(from) {
+            ret[to] = ret[to] + ret[from];
+            indeg[to] -= 1;
+            if indeg[to] == 0 {
+                que.push_back(to);
+            }
+        }
+    }
+    ret
+}
+
+// Same distances as tree, with parents chosen among all shortest paths according to tie.
+// weight is the one passed to mk_dag_by. Lexicographic assumes there is no zero-weight cycle.
+pub fn tie_break<D, A, F>(
+    graph: &A,
+    tree: &ShortestPathTree<D>,
+    weight: F,
+    tie: TieBreak,
+) -> ShortestPathTree<D>
+where A: Graph,
+      D: Add<Output = D> + PartialEq + Copy,
+      F: Fn(A::Value) -> D,
+{
+    let size = graph.size();
+    let dag = mk_dag_by(graph, tree, weight);
+    let adj: Vec<Vec<usize>> = (0..size)
+        .map(|v| {
+            let mut x: Vec<usize> = dag.neighbors(v).map(|x| x.0).collect();
+            x.sort_unstable();
+            x.dedup();
+            x
+        })
+        .collect();
+    let roots: Vec<usize> = (0..size).filter(|&v| tree.is_reachable(v) && tree.parent(v).is_none()).collect();
+    let mut parent = vec![None; size];
+    let mut seen = vec![false; size];
+    match tie {
+        // with sorted neighbors the first visit of a vertex in DFS is along its smallest path
+        TieBreak::Lexicographic => {
+            for &root in &roots {
+                seen[root] = true;
+                let mut stack = vec![(root, 0)];
+                while let Some((v, i)) = stack.pop() {
+                    if i < adj[v].len() {
+                        stack.push((v, i + 1));
+                        let to = adj[v][i];
+                        if !seen[to] {
+                            seen[to] = true;
+                            parent[to] = Some(v);
+                            stack.push((to, 0));
+                        }
+                    }
+                }
+            }
+        }
+        TieBreak::FewestEdges => {
+            let mut que: VecDeque<usize> = roots.iter().cloned().collect();
+            for &root in &roots {
+                seen[root] = true;
+            }
+            while let Some(v) = que.pop_front() {
+                for &to in &adj[v] {
+                    if !seen[to] {
+                        seen[to] = true;
+                        parent[to] = Some(v);
+                        que.push_back(to);
+                    }
+                }
+            }
+        }
+    }
+    ShortestPathTree::new((0..size).map(|v| tree.dist(v).map(|d| (d, parent[v]))).collect())
+}
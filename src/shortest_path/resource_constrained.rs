@@ -0,0 +1,67 @@
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+use std::ops::Add;
+use num::Zero;
+use crate::graph::Graph;
+use crate::shortest_path::dijkstra::Pair;
+
+// (cost, resources used, path in the format of dijkstra::solve_with_goal)
+pub type Found<U, const K: usize> = (U, [U; K], Vec<(usize, usize)>);
+
+struct Label<U, const K: usize> {
+    vertex: usize,
+    cost: U,
+    resource: [U; K],
+    prev: Option<usize>,
+}
+
+fn dominates<U: PartialOrd + Copy, const K: usize>(a: &[U; K], b: &[U; K]) -> bool {
+    a.iter().zip(b.iter()).all(|(x, y)| x <= y)
+}
+
+// Label-setting search over edges valued (cost, [resource; K]), all non-negative.
+// Minimises the cost of a start-goal path whose resources sum to at most budget componentwise.
+pub fn solve<U, A, const K: usize>(
+    graph: &A,
+    start: usize,
+    goal: usize,
+    budget: [U; K],
+) -> Option<Found<U, K>>
+where A: Graph<Value = (U, [U; K])>,
+      U: Add<Output = U> + Zero + PartialOrd + Copy,
+{
+    let size = graph.size();
+    let mut labels = vec![Label { vertex: start, cost: U::zero(), resource: [U::zero(); K], prev: None }];
+    // Pareto set of resource vectors already settled at each vertex, costs are non-decreasing
+    let mut settled: Vec<Vec<[U; K]>> = vec![Vec::new(); size];
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse(Pair::new(0, U::zero())));
+    while let Some(Reverse(pair)) = heap.pop() {
+        let (v, cost, resource) = (labels[pair.label].vertex, labels[pair.label].cost, labels[pair.label].resource);
+        if settled[v].iter().any(|x| dominates(x, &resource)) {
+            continue;
+        }
+        settled[v].push(resource);
+        if v == goal {
+            let mut pass = Vec::new();
+            let mut now = pair.label;
+            while let Some(prev) = labels[now].prev {
+                pass.push((labels[prev].vertex, labels[now].vertex));
+                now = prev;
+            }
+            return Some((cost, resource, pass));
+        }
+        for (to, (c, r)) in graph.neighbors(v) {
+            let mut next = resource;
+            for i in 0..K {
+                next[i] = next[i] + r[i];
+            }
+            if !dominates(&next, &budget) || settled[to].iter().any(|x| dominates(x, &next)) {
+                continue;
+            }
+            labels.push(Label { vertex: to, cost: cost + c, resource: next, prev: Some(pair.label) });
+            heap.push(Reverse(Pair::new(labels.len() - 1, cost + c)));
+        }
+    }
+    None
+}
@@ -0,0 +1,95 @@
+use std::marker::PhantomData;
+use std::ops::{Add, Mul};
+use num::{Bounded, One, Zero};
+use crate::float::Tolerant;
+
+// identity() is the value of the empty path, extend(d, w) the value of a path of value d
+// followed by an edge of weight w, and better(a, b) tells whether a should replace b.
+// Dijkstra additionally needs extend never to produce a better value than d.
+pub trait Semiring {
+    type Value: Copy + PartialOrd;
+    fn identity() -> Self::Value;
+    fn extend(d: Self::Value, w: Self::Value) -> Self::Value;
+    fn better(a: Self::Value, b: Self::Value) -> bool;
+}
+
+// shortest paths
+pub struct MinPlus<U>(PhantomData<U>);
+
+impl<U: Add<Output = U> + Zero + PartialOrd + Copy> Semiring for MinPlus<U> {
+    type Value = U;
+    fn identity() -> U {
+        U::zero()
+    }
+    fn extend(d: U, w: U) -> U {
+        d + w
+    }
+    fn better(a: U, b: U) -> bool {
+        a < b
+    }
+}
+
+// shortest paths ignoring improvements within the tolerance of U, e.g. float::EPS for Float,
+// so that rounding errors neither change parents nor look like negative cycles
+pub struct MinPlusEps<U>(PhantomData<U>);
+
+impl<U: Add<Output = U> + Zero + Tolerant> Semiring for MinPlusEps<U> {
+    type Value = U;
+    fn identity() -> U {
+        U::zero()
+    }
+    fn extend(d: U, w: U) -> U {
+        d + w
+    }
+    fn better(a: U, b: U) -> bool {
+        a.improves(b)
+    }
+}
+
+// longest paths, only meaningful without positive cycles (e.g. in DAGs)
+pub struct MaxPlus<U>(PhantomData<U>);
+
+impl<U: Add<Output = U> + Zero + PartialOrd + Copy> Semiring for MaxPlus<U> {
+    type Value = U;
+    fn identity() -> U {
+        U::zero()
+    }
+    fn extend(d: U, w: U) -> U {
+        d + w
+    }
+    fn better(a: U, b: U) -> bool {
+        a > b
+    }
+}
+
+// widest (bottleneck) paths, the value of a path is its smallest edge
+pub struct MaxMin<U>(PhantomData<U>);
+
+impl<U: Bounded + PartialOrd + Copy> Semiring for MaxMin<U> {
+    type Value = U;
+    fn identity() -> U {
+        U::max_value()
+    }
+    fn extend(d: U, w: U) -> U {
+        if w < d { w } else { d }
+    }
+    fn better(a: U, b: U) -> bool {
+        a > b
+    }
+}
+
+// most reliable paths, the value of a path is the product of its edges (probabilities in [0, 1])
+pub struct MaxTimes<U>(PhantomData<U>);
+
+impl<U: Mul<Output = U> + One + PartialOrd + Copy> Semiring for MaxTimes<U> {
+    type Value = U;
+    fn identity() -> U {
+        U::one()
+    }
+    fn extend(d: U, w: U) -> U {
+        d * w
+    }
+    fn better(a: U, b: U) -> bool {
+        a > b
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+use std::ops::{Add, Div, Mul, Sub};
+use num::{FromPrimitive, Zero};
+use crate::graph::Graph;
+use crate::shortest_path::bellman_ford::{self, Either};
+use crate::shortest_path::ShortestPathTree;
+
+// slf: Small Label First, push to the front when the new label beats the current front.
+// lll: Large Label Last, rotate the front to the back while it exceeds the queue average.
+#[derive(Debug,Clone,Copy,PartialEq,Eq,Default)]
+pub struct Options {
+    pub slf: bool,
+    pub lll: bool,
+}
+
+pub fn solve<U, A>(
+    graph: &A,
+    start: usize,
+) -> Either<U>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Sub<Output = U> + Zero + PartialOrd + Copy,
+{
+    run(graph, start, false, None::<fn(U, U, usize) -> bool>)
+}
+
+pub fn solve_with_options<U, A>(
+    graph: &A,
+    start: usize,
+    options: Options,
+) -> Either<U>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Sub<Output = U> + Mul<Output = U> + Div<Output = U> + Zero + FromPrimitive + PartialOrd + Copy,
+{
+    // d * count > sum without overflowing. Integer division truncates towards zero, so for a
+    // negative sum q may exceed the exact average by less than one, and then d == q is above it.
+    let large = |d: U, sum: U, count: usize| {
+        let count = U::from_usize(count).unwrap();
+        let q = sum / count;
+        d > q || (d == q && q * count > sum)
+    };
+    run(graph, start, options.slf, if options.lll { Some(large) } else { None })
+}
+
+// large(d, sum, count) tells whether the front label d exceeds the average of the count queued labels.
+fn run<U, A, L>(
+    graph: &A,
+    start: usize,
+    slf: bool,
+    large: Option<L>,
+) -> Either<U>
+where A: Graph<Value = U>,
+      U: Add<Output = U> + Sub<Output = U> + Zero + PartialOrd + Copy,
+      L: Fn(U, U, usize) -> bool,
+{
+    let size = graph.size();
+    let lll = large.is_some();
+    let mut ret: Vec<Option<(U, Option<usize>)>> = vec![None; size];
+    ret[start] = Some((U::zero(), None));
+    // number of edges on the walk that last improved each vertex
+    let mut len = vec![0; size];
+    let mut in_queue = vec![false; size];
+    let mut que = VecDeque::new();
+    let mut sum = U::zero();
+    que.push_back(start);
+    in_queue[start] = true;
+    loop {
+        if let Some(large) = &large {
+            // one pass at most, rounding in sum could otherwise make every label look large
+            for _ in 0..que.len() {
+                let front = *que.front().unwrap();
+                if large(ret[front].unwrap().0, sum, que.len()) {
+                    que.rotate_left(1);
+                } else {
+                    break;
+                }
+            }
+        }
+        let from = match que.pop_front() {
+            Some(from) => from,
+            None => break,
+        };
+        in_queue[from] = false;
+        let d = ret[from].unwrap().0;
+        if lll {
+            // start over from the exact value instead of accumulating rounding errors
+            sum = if que.is_empty() { U::zero() } else { sum - d };
+        }
+        for (to, weight) in graph.neighbors(from) {
+            if ret[to].is_some() && ret[to].unwrap().0 <= d + weight {
+                continue;
+            }
+            if lll && in_queue[to] {
+                sum = sum - ret[to].unwrap().0;
+            }
+            ret[to] = Some((d + weight, Some(from)));
+            len[to] = len[from] + 1;
+            if len[to] >= size {
+                // The walk of size edges repeats a vertex, and labels only decrease, so the
+                // cycle between the two visits is negative. The parent pointers usually close
+                // some negative cycle by now, otherwise Bellman-Ford finds one.
+                let parent: Vec<Option<usize>> = ret.iter().map(|x| x.and_then(|x| x.1)).collect();
+                return match find_circuit(&parent) {
+                    Some(circuit) => Either::NegCircuit(circuit),
+                    None => bellman_ford::solve(graph, start),
+                };
+            }
+            if lll {
+                sum = sum + d + weight;
+            }
+            if !in_queue[to] {
+                in_queue[to] = true;
+                let front = que.front().map(|&x| ret[x].unwrap().0);
+                if slf && front.is_some() && d + weight < front.unwrap() {
+                    que.push_front(to);
+                } else {
+                    que.push_back(to);
+                }
+            }
+        }
+    }
+    Either::Shortest(ShortestPathTree::new(ret))
+}
+
+// A long path only hints at a negative cycle, the parent pointers need not close one yet.
+// Once they do, every cycle among them is negative.
+fn find_circuit(parent: &[Option<usize>]) -> Option<Vec<(usize, usize)>> {
+    let size = parent.len();
+    let mut color = vec![0; size];
+    for s in 0..size {
+        let mut v = s;
+        while color[v] == 0 {
+            color[v] = s + 1;
+            match parent[v] {
+                Some(p) => v = p,
+                None => break,
+            }
+        }
+        if color[v] == s + 1 && parent[v].is_some() {
+            let mut circuit = Vec::new();
+            let mut to = v;
+            loop {
+                let from = parent[to].unwrap();
+                circuit.push((from, to));
+                to = from;
+                if to == v {
+                    break;
+                }
+            }
+            circuit.reverse();
+            return Some(circuit);
+        }
+    }
+    None
+}
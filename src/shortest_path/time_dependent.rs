@@ -0,0 +1,85 @@
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+use crate::graph::Graph;
+use crate::shortest_path::dijkstra::Pair;
+use crate::shortest_path::ShortestPathTree;
+
+// Earliest-arrival Dijkstra leaving start at time t0. arrival(from, to, value, t) is the time
+// of reaching to over the edge when at from at time t, None if it can no longer be taken.
+// Edges must be FIFO: being at from later never arrives earlier. Distances are arrival times.
+pub fn solve<U, A, F>(
+    graph: &A,
+    start: usize,
+    t0: U,
+    arrival: F,
+) -> ShortestPathTree<U>
+where A: Graph,
+      U: PartialOrd + Copy,
+      F: Fn(usize, usize, A::Value, U) -> Option<U>,
+{
+    let size = graph.size();
+    let mut ret: Vec<Option<(U, Option<usize>)>> = vec![None; size];
+    ret[start] = Some((t0, None));
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse(Pair::new(start, t0)));
+    let mut set = vec![false; size];
+    while let Some(Reverse(pair)) = heap.pop() {
+        if set[pair.label] {
+            continue;
+        }
+        set[pair.label] = true;
+        for (to, value) in graph.neighbors(pair.label) {
+            let t = match arrival(pair.label, to, value, pair.value) {
+                Some(t) => t,
+                None => continue,
+            };
+            if !set[to] && (ret[to].is_none() || t < ret[to].unwrap().0) {
+                ret[to] = Some((t, Some(pair.label)));
+                heap.push(Reverse(Pair::new(to, t)));
+            }
+        }
+    }
+    ShortestPathTree::new(ret)
+}
+
+// Scheduled connections, a graph over them stores the id returned by add as edge value.
+#[derive(Debug,Clone,Default)]
+pub struct Timetable<U> {
+    // (departure, arrival) of each trip sorted by departure
+    trips: Vec<Vec<(U, U)>>,
+    // earliest[id][i] is the earliest arrival among trips[id][i..]
+    earliest: Vec<Vec<U>>,
+}
+
+impl<U: PartialOrd + Copy> Timetable<U> {
+    pub fn new() -> Self {
+        Timetable {
+            trips: Vec::new(),
+            earliest: Vec::new(),
+        }
+    }
+
+    // trips as (departure, arrival) in any order
+    pub fn add(&mut self, mut trips: Vec<(U, U)>) -> usize {
+        trips.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let mut earliest: Vec<U> = trips.iter().map(|x| x.1).collect();
+        for i in (1..earliest.len()).rev() {
+            if earliest[i] < earliest[i - 1] {
+                earliest[i - 1] = earliest[i];
+            }
+        }
+        self.trips.push(trips);
+        self.earliest.push(earliest);
+        self.trips.len() - 1
+    }
+
+    // earliest arrival over connection id when ready to depart at time t
+    pub fn arrival(&self, id: usize, t: U) -> Option<U> {
+        let i = self.trips[id].partition_point(|x| x.0 < t);
+        self.earliest[id].get(i).copied()
+    }
+
+    pub fn solve<A: Graph<Value = usize>>(&self, graph: &A, start: usize, t0: U) -> ShortestPathTree<U> {
+        solve(graph, start, t0, |_, _, id, t| self.arrival(id, t))
+    }
+}
@@ -0,0 +1,167 @@
+use crate::graph::*;
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum EdgeKind {
+    Tree,
+    // to an ancestor still on the stack, i.e. closes a cycle
+    Back,
+    // to an already finished descendant
+    Forward,
+    // to a finished vertex in another subtree or an earlier tree
+    Cross,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Event {
+    Pre(usize),
+    Post(usize),
+    Edge(usize, usize, EdgeKind),
+}
+
+// Discovery and finish times share one clock, so u is an ancestor of v exactly when
+// discovery(u) <= discovery(v) and finish(v) <= finish(u).
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct Dfs {
+    discovery: Vec<Option<usize>>,
+    finish: Vec<Option<usize>>,
+    parent: Vec<Option<usize>>,
+}
+
+impl Dfs {
+    pub fn discovery(&self, v: usize) -> Option<usize> {
+        self.discovery[v]
+    }
+
+    pub fn finish(&self, v: usize) -> Option<usize> {
+        self.finish[v]
+    }
+
+    pub fn parent(&self, v: usize) -> Option<usize> {
+        self.parent[v]
+    }
+
+    pub fn is_visited(&self, v: usize) -> bool {
+        self.discovery[v].is_some()
+    }
+}
+
+pub fn dfs<A: Graph>(graph: &A, roots: &[usize]) -> Dfs {
+    dfs_with(graph, roots, |_| {})
+}
+
+// Iterative DFS from each unvisited root in turn, reporting events in the order a recursive
+// DFS would. Pass (0..graph.size()).collect::<Vec<_>>() as roots to cover the whole graph.
+pub fn dfs_with<A: Graph, F: FnMut(Event)>(graph: &A, roots: &[usize], mut visit: F) -> Dfs {
+    let size = graph.size();
+    let mut discovery = vec![None; size];
+    let mut finish = vec![None; size];
+    let mut parent = vec![None; size];
+    let mut time = 0;
+    let mut stack: Vec<(usize, A::Iter)> = Vec::new();
+    for &root in roots {
+        if discovery[root].is_some() {
+            continue;
+        }
+        discovery[root] = Some(time);
+        time += 1;
+        visit(Event::Pre(root));
+        stack.push((root, graph.neighbors(root)));
+        while let Some((from, iter)) = stack.last_mut() {
+            let from = *from;
+            match iter.next() {
+                Some((to, _)) => {
+                    let kind = match (discovery[to], finish[to]) {
+                        (None, _) => EdgeKind::Tree,
+                        (Some(_), None) => EdgeKind::Back,
+                        (Some(d), Some(_)) if discovery[from].unwrap() < d => EdgeKind::Forward,
+                        _ => EdgeKind::Cross,
+                    };
+                    visit(Event::Edge(from, to, kind));
+                    if kind == EdgeKind::Tree {
+                        discovery[to] = Some(time);
+                        time += 1;
+                        parent[to] = Some(from);
+                        visit(Event::Pre(to));
+                        stack.push((to, graph.neighbors(to)));
+                    }
+                }
+                None => {
+                    finish[from] = Some(time);
+                    time += 1;
+                    visit(Event::Post(from));
+                    stack.pop();
+                }
+            }
+        }
+    }
+    Dfs { discovery, finish, parent }
+}
+
+// Vertices grouped by their number of edges from the nearest root.
+pub fn bfs_layers<A: Graph>(graph: &A, roots: &[usize]) -> Vec<Vec<usize>> {
+    let mut visited = vec![false; graph.size()];
+    let mut layer = Vec::new();
+    for &root in roots {
+        if !visited[root] {
+            visited[root] = true;
+            layer.push(root);
+        }
+    }
+    let mut ret = Vec::new();
+    while !layer.is_empty() {
+        let mut next = Vec::new();
+        for &from in &layer {
+            for (to, _) in graph.neighbors(from) {
+                if !visited[to] {
+                    visited[to] = true;
+                    next.push(to);
+                }
+            }
+        }
+        ret.push(layer);
+        layer = next;
+    }
+    ret
+}
+
+
+#[test]
+fn test1() {
+    let mut graph = ListGraph::new(6);
+    graph.add_edge(0,1,());
+    graph.add_edge(1,2,());
+    graph.add_edge(2,0,());
+    graph.add_edge(0,2,());
+    graph.add_edge(3,2,());
+    graph.add_edge(3,4,());
+    let mut events = Vec::new();
+    let tree = dfs_with(&graph,&[0,3,5],|e| events.push(e));
+    use EdgeKind::*;
+    use Event::*;
+    assert_eq!(events,vec![
+        Pre(0),Edge(0,1,Tree),Pre(1),Edge(1,2,Tree),Pre(2),Edge(2,0,Back),Post(2),Post(1),Edge(0,2,Forward),Post(0),
+        Pre(3),Edge(3,2,Cross),Edge(3,4,Tree),Pre(4),Post(4),Post(3),
+        Pre(5),Post(5),
+    ]);
+    assert_eq!((tree.discovery(2),tree.finish(2)),(Some(2),Some(3)));
+    assert_eq!((tree.discovery(0),tree.finish(0)),(Some(0),Some(5)));
+    assert_eq!(tree.parent(4),Some(3));
+    assert_eq!(tree.parent(3),None);
+    assert!(!dfs(&graph,&[4]).is_visited(0));
+
+    assert_eq!(bfs_layers(&graph,&[3]),vec![vec![3],vec![2,4],vec![0],vec![1]]);
+    assert_eq!(bfs_layers(&graph,&[1,3,1]),vec![vec![1,3],vec![2,4],vec![0]]);
+}
+
+#[test]
+fn test_long_path() {
+    let size = 1_000_000;
+    let mut graph = ListGraph::new(size);
+    for v in 1..size {
+        graph.add_edge(v - 1,v,());
+    }
+    let tree = dfs(&graph,&[0]);
+    assert_eq!(tree.finish(0),Some(2 * size - 1));
+    assert_eq!(tree.parent(size - 1),Some(size - 2));
+    assert_eq!(bfs_layers(&graph,&[0]).len(),size);
+}